@@ -0,0 +1,109 @@
+use itertools::Itertools;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::Ident;
+
+use crate::{constants::*, helper::*, msi_tables::FieldInformation};
+
+/// Generates a builder with a named setter for every field of the DAO that is not filled in
+/// automatically. Nullable fields default to `None`, every other field must be set before `build`
+/// is called.
+pub fn generate_builder_tokens(
+    target_name: &str,
+    dao_name: &Ident,
+    all_fields: &[FieldInformation],
+) -> TokenStream {
+    let fields = all_fields
+        .iter()
+        .filter(|field| field.is_constructor_argument())
+        .collect_vec();
+    let builder_name = format_ident!("{dao_name}{BUILDER_SUFFIX}");
+    let error_name = error_from_name(target_name);
+
+    let field_idents = fields.iter().map(|f| f.ident().clone()).collect_vec();
+    // Nullable fields are stored as they are so they can stay `None`. Required fields are wrapped
+    // in an `Option` until they are set.
+    let stored_types = fields
+        .iter()
+        .map(|field| {
+            let ty = &field.ty;
+            if field.is_nullable() {
+                ty.clone()
+            } else {
+                syn::parse_quote!(::core::option::Option<#ty>)
+            }
+        })
+        .collect_vec();
+
+    let setters = fields.iter().map(|field| {
+        let field_ident = field.ident();
+        let setter_comment = &format!("Sets the `{}` column.", field.column_name());
+        // Nullable fields written as `Option<T>` are set from a `T`. The inner type of an alias is
+        // unknown, so those are set from anything that converts into the alias, which includes
+        // the inner type itself.
+        let (value_type, value) = match option_inner_type(&field.ty) {
+            Some(inner) if field.is_nullable() => (inner, quote! { Some(#field_ident.into()) }),
+            _ if field.is_nullable() => (&field.ty, quote! { #field_ident.into() }),
+            _ => (&field.ty, quote! { Some(#field_ident.into()) }),
+        };
+        quote! {
+            #[doc = #setter_comment]
+            pub fn #field_ident(mut self, #field_ident: impl ::core::convert::Into<#value_type>) -> Self {
+                self.#field_ident = #value;
+                self
+            }
+        }
+    });
+
+    let filled_values = all_fields.iter().filter_map(|field| {
+        let field_ident = field.ident();
+        let value = field.fill_expression(dao_name)?;
+        Some(quote! { #field_ident: #value })
+    });
+    let field_values = fields.iter().map(|field| {
+        let field_ident = field.ident();
+        let field_name = field_ident.to_string();
+        if field.is_nullable() {
+            quote! { #field_ident: self.#field_ident }
+        } else {
+            quote! {
+                #field_ident: self.#field_ident.ok_or(#error_name::MissingField {
+                    field: #field_name,
+                })?
+            }
+        }
+    });
+
+    let builder_comment = &format!(
+        "Builds a `{dao_name}` one named column at a time. Create one with `{dao_name}::builder`."
+    );
+    quote! {
+        #[doc = #builder_comment]
+        #[derive(Clone, Debug, Default)]
+        pub struct #builder_name {
+            #( #field_idents: #stored_types , )*
+        }
+
+        impl #builder_name {
+            #( #setters )*
+
+            /// Creates the row. Fails if a column that is not nullable was never set or a value
+            /// does not fit in its column.
+            pub fn build(self) -> ::core::result::Result<#dao_name, #error_name> {
+                let dao = #dao_name {
+                    #( #field_values , )*
+                    #( #filled_values , )*
+                };
+                dao.check_constraints()?;
+                Ok(dao)
+            }
+        }
+
+        impl #dao_name {
+            /// Starts building a row with every nullable column set to `None`.
+            pub fn builder() -> #builder_name {
+                #builder_name::default()
+            }
+        }
+    }
+}
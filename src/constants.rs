@@ -1,5 +1,24 @@
 // -- Default string appenders ------------------------------------------------
+pub const BUILDER_SUFFIX: &str = "Builder";
+pub const CONTAINER_SUFFIX: &str = "Container";
 pub const DAO_SUFFIX: &str = "Dao";
+pub const ERROR_SUFFIX: &str = "Error";
 pub const GENERATOR_SUFFIX: &str = "Generator";
 pub const IDENTIFIER_SUFFIX: &str = "Identifier";
+pub const KEY_SUFFIX: &str = "Key";
+pub const KIND_SUFFIX: &str = "Kind";
+pub const REGISTRY_SUFFIX: &str = "Registry";
 pub const TABLE_SUFFIX: &str = "Table";
+
+// -- MSI limits ----------------------------------------------------------------
+// The longest string column that can be declared. A length of 0 means the length is unlimited.
+pub const MAX_COLUMN_LENGTH: u32 = 255;
+// Lengths used for string columns that do not define one. Only categories whose values have the
+// same maximum length in every table of the MSI schema are listed. Identifier columns are not,
+// e.g. `Feature` is 38 long and `Control` 50.
+pub const DEFAULT_COLUMN_LENGTHS: &[(msi::Category, u32)] = &[(msi::Category::Guid, 38)];
+pub const MAX_TABLE_NAME_LENGTH: usize = 31;
+pub const MAX_COLUMN_NAME_LENGTH: usize = 64;
+pub const MAX_COLUMNS: usize = 32;
+// Tables that every MSI database already contains and that cannot be defined again.
+pub const RESERVED_TABLE_NAMES: &[&str] = &["_Columns", "_Storages", "_Streams", "_Tables"];
@@ -0,0 +1,183 @@
+use itertools::Itertools;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Ident;
+
+use crate::{msi_tables::FieldInformation, paths::CratePaths};
+
+/// A value listed in the `set` of a column.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum SetValue {
+    Int(i32),
+    Str(String),
+}
+
+impl std::fmt::Display for SetValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SetValue::Int(value) => write!(f, "{value}"),
+            SetValue::Str(value) => f.write_str(value),
+        }
+    }
+}
+
+/// The `min`, `max` and `set` attributes of a column with their literals parsed.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct ColumnConstraints {
+    pub min: Option<i32>,
+    pub max: Option<i32>,
+    pub set: Option<Vec<SetValue>>,
+}
+
+impl ColumnConstraints {
+    pub fn parse(field: &FieldInformation) -> darling::Result<ColumnConstraints> {
+        let mut errors = darling::Error::accumulator();
+        let min = field
+            .min
+            .as_ref()
+            .and_then(|min| errors.handle(parse_int(min)));
+        let max = field
+            .max
+            .as_ref()
+            .and_then(|max| errors.handle(parse_int(max)));
+        let set = field
+            .set
+            .as_ref()
+            .and_then(|set| errors.handle(parse_set(set)));
+        errors.finish_with(ColumnConstraints { min, max, set })
+    }
+
+    /// The values of the set joined the way the `Set` column of `_Validation` expects them.
+    pub fn set_string(&self) -> Option<String> {
+        Some(self.set.as_ref()?.iter().join(";"))
+    }
+}
+
+fn parse_int(expr: &syn::Expr) -> darling::Result<i32> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(int),
+            ..
+        }) => Ok(int.base10_parse::<i32>()?),
+        syn::Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr: inner,
+            ..
+        }) => parse_int(inner)?
+            .checked_neg()
+            .ok_or_else(|| darling::Error::custom("Value is out of range").with_span(expr)),
+        syn::Expr::Group(group) => parse_int(&group.expr),
+        _ => Err(darling::Error::custom("Expected an integer literal").with_span(expr)),
+    }
+}
+
+fn parse_set(expr: &syn::Expr) -> darling::Result<Vec<SetValue>> {
+    let syn::Expr::Array(array) = expr else {
+        return Err(
+            darling::Error::custom("Expected an array of literals such as `[1, 2]`")
+                .with_span(expr),
+        );
+    };
+    let mut errors = darling::Error::accumulator();
+    let values = array
+        .elems
+        .iter()
+        .filter_map(|value| {
+            errors.handle(match value {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(value),
+                    ..
+                }) => Ok(SetValue::Str(value.value())),
+                _ => parse_int(value).map(SetValue::Int),
+            })
+        })
+        .collect_vec();
+    errors.finish_with(values)
+}
+
+/// Generates the statements that return an error when `value`, the `msi::Value` of the field, is
+/// longer than the column or breaks one of the field's constraints.
+pub fn generate_constraint_check_tokens(
+    field: &FieldInformation,
+    error_name: &Ident,
+    paths: &CratePaths,
+) -> TokenStream {
+    // Invalid constraints are reported while generating the column definitions.
+    let Ok(constraints) = ColumnConstraints::parse(field) else {
+        return TokenStream::new();
+    };
+    let msi = &paths.msi;
+    let column_name = field.column_name();
+    let invalid_value = |reason: String| {
+        quote! {
+            return Err(#error_name::NotAllowed {
+                column: #column_name,
+                value: value.to_string(),
+                reason: ::std::string::String::from(#reason),
+            });
+        }
+    };
+
+    let mut checks = TokenStream::new();
+    // Length is checked on the rendered value as that is what is written to the database. A length
+    // of 0 means the column is unlimited.
+    if let Some(length) = field.column_length() {
+        let field_name = field.ident().to_string();
+        checks.extend(quote! {
+            if let #msi::Value::Str(text) = &value {
+                let max_length = (#length) as usize;
+                let length = text.chars().count();
+                if max_length != 0 && length > max_length {
+                    return Err(#error_name::TooLong {
+                        field: #field_name,
+                        column: #column_name,
+                        length,
+                        max_length,
+                    });
+                }
+            }
+        });
+    }
+    if let Some(min) = constraints.min {
+        let error = invalid_value(format!("must be at least {min}"));
+        checks.extend(quote! {
+            if let #msi::Value::Int(number) = &value {
+                if *number < #min {
+                    #error
+                }
+            }
+        });
+    }
+    if let Some(max) = constraints.max {
+        let error = invalid_value(format!("must be at most {max}"));
+        checks.extend(quote! {
+            if let #msi::Value::Int(number) = &value {
+                if *number > #max {
+                    #error
+                }
+            }
+        });
+    }
+    if let Some(set) = &constraints.set {
+        let error = invalid_value(format!("must be one of {}", set.iter().join(", ")));
+        let ints = set.iter().filter_map(|value| match value {
+            SetValue::Int(value) => Some(value),
+            SetValue::Str(_) => None,
+        });
+        let strs = set.iter().filter_map(|value| match value {
+            SetValue::Int(_) => None,
+            SetValue::Str(value) => Some(value),
+        });
+        checks.extend(quote! {
+            let allowed = match &value {
+                #msi::Value::Int(number) => [#(#ints),*].contains(number),
+                #msi::Value::Str(text) => [#(#strs),*].contains(&text.as_str()),
+                _ => true,
+            };
+            if !allowed {
+                #error
+            }
+        });
+    }
+    checks
+}
@@ -0,0 +1,519 @@
+use itertools::Itertools;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::Ident;
+
+use crate::{
+    helper::*,
+    lookup::lookup_value,
+    msi_tables::{FieldInformation, TableList, VariantInformation},
+    paths::CratePaths,
+};
+
+/// A table of the list as seen by the container.
+struct ContainedTable<'a> {
+    // The variant of the list and of its `{List}Kind`.
+    variant: &'a Ident,
+    // The name of the table in the MSI.
+    name: String,
+    // The field of the container that holds the table.
+    field: Ident,
+    fields: &'a [FieldInformation],
+}
+
+/// Generates the container that holds one of every table in an `msi_table_list!` and the checks
+/// that need more than one of its tables.
+pub fn generate_container_tokens(
+    list_name: &str,
+    items: &[VariantInformation],
+    tables: &TableList,
+    paths: &CratePaths,
+) -> TokenStream {
+    let container_ident = container_from_name(list_name);
+    let contained = items
+        .iter()
+        .map(|variant| {
+            let name = capitalize(&variant.ident.to_string());
+            ContainedTable {
+                variant: &variant.ident,
+                field: format_ident!("{}", pascal_case_to_snake_case(&name)),
+                name,
+                fields: &variant.fields.fields,
+            }
+        })
+        .collect_vec();
+
+    let table_fields = contained.iter().map(|table| &table.field).collect_vec();
+    let table_types = contained
+        .iter()
+        .map(|table| table_from_name(&table.name))
+        .collect_vec();
+    let container_comment = &format!(
+        "One of every table in `{list_name}`. Tables created with `new` share the identifiers used \
+        by their generators and the registry of their `unique_scope` columns."
+    );
+    let new_tokens = generate_new_for_container(&contained, tables);
+    let reference_tokens = generate_check_references(list_name, &contained, tables, paths);
+    let cascade_tokens = generate_remove_cascade(list_name, &contained, tables, paths);
+
+    quote! {
+        #[doc = #container_comment]
+        #[derive(Clone, Debug, PartialEq)]
+        pub struct #container_ident {
+            #( pub #table_fields: #table_types, )*
+        }
+
+        impl #container_ident {
+            #new_tokens
+        }
+
+        impl ::core::default::Default for #container_ident {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        #reference_tokens
+        #cascade_tokens
+    }
+}
+
+fn generate_new_for_container(contained: &[ContainedTable], tables: &TableList) -> TokenStream {
+    let mut shared = TokenStream::new();
+    if contained.iter().any(|table| {
+        table
+            .fields
+            .iter()
+            .any(FieldInformation::is_generated_identifier)
+    }) {
+        shared.extend(quote! {
+            let used = ::std::rc::Rc::new(::std::cell::RefCell::new(::std::vec::Vec::new()));
+        });
+    }
+    let registry = tables.registry().filter(|_| {
+        contained.iter().any(|table| {
+            table
+                .fields
+                .iter()
+                .any(|field| field.unique_scope.is_some())
+        })
+    });
+    if let Some(registry) = registry {
+        shared.extend(quote! {
+            let registry = ::std::rc::Rc::new(::std::cell::RefCell::new(#registry::default()));
+        });
+    }
+
+    let table_values = contained.iter().map(|table| {
+        let field = &table.field;
+        let table_type = table_from_name(&table.name);
+        let mut value = if table
+            .fields
+            .iter()
+            .any(FieldInformation::is_generated_identifier)
+        {
+            quote! { #table_type::new(used.clone()) }
+        } else {
+            quote! { #table_type::default() }
+        };
+        if registry.is_some()
+            && table
+                .fields
+                .iter()
+                .any(|field| field.unique_scope.is_some())
+        {
+            value = quote! { #value.with_registry(registry.clone()) };
+        }
+        quote! { #field: #value, }
+    });
+    quote! {
+        /// Creates every table empty.
+        pub fn new() -> Self {
+            #shared
+            Self {
+                #(#table_values)*
+            }
+        }
+    }
+}
+
+/// A foreign key from a column of one table of the list into another table of the list.
+struct Reference<'a> {
+    table: &'a ContainedTable<'a>,
+    field: &'a FieldInformation,
+    referenced: &'a ContainedTable<'a>,
+    referenced_field: &'a FieldInformation,
+}
+
+/// Every foreign key between the tables of the list.
+fn references<'a>(
+    contained: &'a [ContainedTable<'a>],
+    tables: &TableList<'a>,
+) -> Vec<Reference<'a>> {
+    let mut references = Vec::new();
+    for table in contained {
+        for field in table.fields {
+            let Some(foreign_key) = field.foreign_key() else {
+                continue;
+            };
+            // Foreign keys into tables that are not in the list are reported with the column.
+            let Ok(Some(referenced_field)) = tables.referenced_field(foreign_key) else {
+                continue;
+            };
+            let Some(referenced) = contained
+                .iter()
+                .find(|table| table.name == foreign_key.value())
+            else {
+                continue;
+            };
+            references.push(Reference {
+                table,
+                field,
+                referenced,
+                referenced_field,
+            });
+        }
+    }
+    references
+}
+
+/// Whether the rows of the table are found by a primary key.
+fn has_primary_key(table: &ContainedTable) -> bool {
+    table.fields.iter().any(|field| field.primary_key)
+}
+
+/// The values of the primary key of `entry`, or of the whole row if the table has no primary key.
+fn generate_row_key(fields: &[FieldInformation], paths: &CratePaths) -> TokenStream {
+    let msi = &paths.msi;
+    let msi_dao = paths.msi_dao();
+    let primary_key = fields
+        .iter()
+        .filter(|field| field.primary_key)
+        .collect_vec();
+    if primary_key.is_empty() {
+        return quote! { #msi_dao::to_row(entry) };
+    }
+    let key_idents = primary_key.iter().map(|field| field.ident());
+    quote! { ::std::vec![ #( #msi::ToValue::to_value(&entry.#key_idents) ),* ] }
+}
+
+fn generate_check_references(
+    list_name: &str,
+    contained: &[ContainedTable],
+    tables: &TableList,
+    paths: &CratePaths,
+) -> TokenStream {
+    let msi = &paths.msi;
+    let container_ident = container_from_name(list_name);
+    let reference_ident = format_ident!("{list_name}DanglingReference");
+    let kind_ident = kind_from_name(list_name);
+
+    let checks = references(contained, tables)
+        .into_iter()
+        .map(|reference| {
+            let table_field = &reference.table.field;
+            let variant = reference.table.variant;
+            let column_name = reference.field.column_name();
+            let value = lookup_value(reference.field, quote! { entry });
+            let row_key = generate_row_key(reference.table.fields, paths);
+            let referenced_table = &reference.referenced.field;
+            let referenced_variant = reference.referenced.variant;
+            let referenced_value = lookup_value(reference.referenced_field, quote! { entry });
+            quote! {
+                {
+                    let referenced = self
+                        .#referenced_table
+                        .entries
+                        .iter()
+                        .filter_map(|entry| #referenced_value)
+                        .map(#msi::ToValue::to_value)
+                        .collect::<::std::collections::HashSet<_>>();
+                    for entry in &self.#table_field.entries {
+                        let Some(value) = #value else {
+                            continue;
+                        };
+                        let value = #msi::ToValue::to_value(value);
+                        if !referenced.contains(&value) {
+                            dangling.push(#reference_ident {
+                                table: #kind_ident::#variant,
+                                key: #row_key,
+                                column: #column_name,
+                                value,
+                                referenced_table: #kind_ident::#referenced_variant,
+                            });
+                        }
+                    }
+                }
+            }
+        })
+        .collect_vec();
+
+    let message = "Column `{column}` of row ({key}) in table `{table:?}` references `{value}` \
+        which is not in table `{referenced_table:?}`";
+    let body = if checks.is_empty() {
+        quote! { ::std::vec::Vec::new() }
+    } else {
+        quote! {
+            let mut dangling = ::std::vec::Vec::new();
+            #(#checks)*
+            dangling
+        }
+    };
+    quote! {
+        /// A foreign key whose value is not in the table that it references.
+        #[derive(Clone, Debug, PartialEq)]
+        pub struct #reference_ident {
+            /// The table of the row holding the foreign key.
+            pub table: #kind_ident,
+            /// The primary key of the row, or every value of the row if its table has no primary
+            /// key.
+            pub key: ::std::vec::Vec<#msi::Value>,
+            /// The column holding the foreign key.
+            pub column: &'static str,
+            /// The value that is missing from the referenced table.
+            pub value: #msi::Value,
+            /// The table that the column references.
+            pub referenced_table: #kind_ident,
+        }
+
+        impl ::std::fmt::Display for #reference_ident {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                let key = self.key.iter().map(::std::string::ToString::to_string).collect::<::std::vec::Vec<_>>();
+                write!(
+                    f,
+                    #message,
+                    column = self.column,
+                    key = key.join(", "),
+                    table = self.table,
+                    value = self.value,
+                    referenced_table = self.referenced_table
+                )
+            }
+        }
+
+        impl #container_ident {
+            /// Every foreign key of every table whose value is not in the table that it references.
+            /// Null values are never reported.
+            pub fn check_references(&self) -> ::std::vec::Vec<#reference_ident> {
+                #body
+            }
+        }
+    }
+}
+
+fn generate_remove_cascade(
+    list_name: &str,
+    contained: &[ContainedTable],
+    tables: &TableList,
+    paths: &CratePaths,
+) -> TokenStream {
+    let msi = &paths.msi;
+    let msi_table_kind = paths.msi_table_kind();
+    let msi_dao = paths.msi_dao();
+    let container_ident = container_from_name(list_name);
+    let dao_enum_ident = dao_from_name(list_name);
+    let error_ident = error_from_name(list_name);
+    let cascade_ident = format_ident!("{list_name}Cascade");
+    let row_ident = format_ident!("{list_name}CascadeRow");
+    let kind_ident = kind_from_name(list_name);
+    let references = references(contained, tables);
+
+    // Rows waiting to be removed are held by their primary key so they are found through the key
+    // index of their table. Rows of tables without a primary key are held by their values.
+    let row_types = contained
+        .iter()
+        .map(|table| {
+            if has_primary_key(table) {
+                let key_ident = key_from_name(&table.name);
+                quote! { #key_ident }
+            } else {
+                quote! { ::std::vec::Vec<#msi::Value> }
+            }
+        })
+        .collect_vec();
+    let row_values = contained
+        .iter()
+        .map(|table| {
+            if has_primary_key(table) {
+                quote! { entry.key() }
+            } else {
+                quote! { #msi_dao::to_row(entry) }
+            }
+        })
+        .collect_vec();
+
+    let variants = contained.iter().map(|table| table.variant).collect_vec();
+    let error_names = contained
+        .iter()
+        .map(|table| error_from_name(&table.name))
+        .collect_vec();
+
+    let first_arms = contained.iter().zip(&row_values).map(|(table, row_value)| {
+        let variant = table.variant;
+        let table_field = &table.field;
+        let row_key = generate_row_key(table.fields, paths);
+        quote! {
+            #kind_ident::#variant => self
+                .#table_field
+                .entries
+                .iter()
+                .find(|&entry| #row_key == key)
+                .map(|entry| #row_ident::#variant(#row_value)),
+        }
+    });
+
+    let remove_arms = contained.iter().map(|table| {
+        let variant = table.variant;
+        let table_field = &table.field;
+        let position = if has_primary_key(table) {
+            quote! { self.#table_field.position_by_key(&key) }
+        } else {
+            quote! {
+                self.#table_field
+                    .entries
+                    .iter()
+                    .position(|entry| #msi_dao::to_row(entry) == key)
+            }
+        };
+        quote! {
+            #row_ident::#variant(key) => {
+                let Some(position) = #position else {
+                    continue;
+                };
+                let dao = self
+                    .#table_field
+                    .remove(position)
+                    .map_err(#error_ident::#variant)?;
+                #dao_enum_ident::#variant(dao)
+            }
+        }
+    });
+
+    let dependent_arms = contained.iter().map(|referenced| {
+        let variant = referenced.variant;
+        let dependents = references
+            .iter()
+            .filter(|reference| reference.referenced.name == referenced.name)
+            .map(|reference| {
+                let table_field = &reference.table.field;
+                let dependent_variant = reference.table.variant;
+                let field_ident = reference.field.ident();
+                let value = lookup_value(reference.field, quote! { entry });
+                let referenced_value = lookup_value(reference.referenced_field, quote! { dao });
+                let position = contained
+                    .iter()
+                    .position(|table| table.name == reference.table.name)
+                    .expect("references are between tables of the list");
+                let row_value = &row_values[position];
+                let delete = quote! {
+                    for entry in &self.#table_field.entries {
+                        if #value.map(#msi::ToValue::to_value).as_ref() == Some(&value) {
+                            pending.push(#row_ident::#dependent_variant(#row_value));
+                        }
+                    }
+                };
+                // Only nullable references can be set to null.
+                let update = if reference.field.is_nullable() {
+                    quote! {
+                        if cascade == #cascade_ident::NullOut {
+                            let entries = #msi_table_kind::entries_mut(&mut self.#table_field);
+                            for entry in entries.iter_mut() {
+                                if #value.map(#msi::ToValue::to_value).as_ref() == Some(&value) {
+                                    entry.#field_ident = None;
+                                }
+                            }
+                        } else {
+                            #delete
+                        }
+                    }
+                } else {
+                    delete
+                };
+                quote! {
+                    if let Some(value) = #referenced_value {
+                        let value = #msi::ToValue::to_value(value);
+                        #update
+                    }
+                }
+            })
+            .collect_vec();
+        if dependents.is_empty() {
+            return quote! { #dao_enum_ident::#variant(_) => {} };
+        }
+        quote! {
+            #dao_enum_ident::#variant(dao) => {
+                #(#dependents)*
+            }
+        }
+    });
+
+    let error_comment = &format!(
+        "The error of the table that refused a change made through the `{container_ident}`."
+    );
+    quote! {
+        /// How `remove_cascade` treats the rows that reference a removed row.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub enum #cascade_ident {
+            /// Removes every row that references a removed row.
+            Delete,
+            /// Sets nullable references to null and removes the rows holding any other
+            /// reference.
+            NullOut,
+        }
+
+        /// A row that `remove_cascade` still has to remove.
+        enum #row_ident {
+            #( #variants(#row_types), )*
+        }
+
+        #[doc = #error_comment]
+        #[derive(Clone, Debug, PartialEq)]
+        pub enum #error_ident {
+            #( #variants(#error_names), )*
+        }
+
+        impl ::std::fmt::Display for #error_ident {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                match self {
+                    #( Self::#variants(error) => ::std::fmt::Display::fmt(error, f), )*
+                }
+            }
+        }
+
+        impl ::std::error::Error for #error_ident {}
+
+        impl #container_ident {
+            /// Removes the row of `table` with the given primary key, or with the given values if
+            /// the table has no primary key, along with every row that references it. Rows that
+            /// reference a removed row are handled as `cascade` says. Returns every removed row.
+            ///
+            /// The row itself is found by comparing `key` with every entry of `table`. The rows
+            /// that reference it are then found by their primary key.
+            ///
+            /// Stops at the first row that the `on_remove` of its table refuses. Rows removed
+            /// before then stay removed.
+            pub fn remove_cascade(
+                &mut self,
+                table: #kind_ident,
+                key: &[#msi::Value],
+                cascade: #cascade_ident,
+            ) -> ::core::result::Result<::std::vec::Vec<#dao_enum_ident>, #error_ident> {
+                let mut removed = ::std::vec::Vec::new();
+                let first = match table {
+                    #(#first_arms)*
+                };
+                let mut pending = first.into_iter().collect::<::std::vec::Vec<_>>();
+                while let Some(row) = pending.pop() {
+                    let dao = match row {
+                        #(#remove_arms)*
+                    };
+                    match &dao {
+                        #(#dependent_arms)*
+                    }
+                    removed.push(dao);
+                }
+                Ok(removed)
+            }
+        }
+    }
+}
@@ -1,34 +1,63 @@
+use crate::constraint::generate_constraint_check_tokens;
 use crate::helper::*;
 use crate::msi_tables::FieldInformation;
+use crate::paths::CratePaths;
 use itertools::Itertools;
 use proc_macro2::TokenStream;
 use quote::quote;
 use syn::Ident;
 
+/// Generates the DAO for a table. When `user_dao` is set the struct was written by the user (e.g.
+/// `#[derive(MsiTable)]`) so only the impls are generated for it and the fields keep whatever
+/// visibility the user gave them.
 pub fn generate_dao_tokens(
     target_name: &str,
+    user_dao: Option<&Ident>,
     primary_identifier: &Option<&FieldInformation>,
-    fields: &Vec<FieldInformation>,
+    fields: &[FieldInformation],
+    unique_keys: &[Vec<&FieldInformation>],
+    paths: &CratePaths,
 ) -> TokenStream {
-    let dao_struct_ident = dao_from_name(target_name);
-
-    let dao_struct_tokens = generate_dao_struct_definition(&dao_struct_ident, fields);
-    let dao_impl_tokens = generate_new_for_dao(target_name, fields);
+    let (dao_struct_ident, dao_struct_tokens, getter_tokens) = match user_dao {
+        Some(dao_struct_ident) => (dao_struct_ident.clone(), quote! {}, quote! {}),
+        None => {
+            let dao_struct_ident = dao_from_name(target_name);
+            (
+                dao_struct_ident.clone(),
+                generate_dao_struct_definition(&dao_struct_ident, fields),
+                generate_getters_for_dao(&dao_struct_ident, fields),
+            )
+        }
+    };
+    let dao_impl_tokens = generate_new_for_dao(target_name, &dao_struct_ident, fields);
+    let check_constraints_tokens =
+        generate_check_constraints_for_dao(target_name, &dao_struct_ident, fields, paths);
+    let from_row_tokens = generate_from_row_for_dao(target_name, &dao_struct_ident, fields, paths);
     let primary_identifier_impl_tokens =
-        generate_primary_identifier_impl_definition(primary_identifier, &dao_struct_ident);
-    let msi_dao_impl_tokens = generate_msi_dao_impl_definition(&dao_struct_ident, fields);
+        generate_primary_identifier_impl_definition(primary_identifier, &dao_struct_ident, paths);
+    let conflicting_columns_tokens =
+        generate_conflicting_columns_for_dao(&dao_struct_ident, unique_keys);
+    let scoped_values_tokens = generate_scoped_values_for_dao(&dao_struct_ident, fields, paths);
+    let key_tokens = generate_key_for_dao(target_name, &dao_struct_ident, fields);
+    let msi_dao_impl_tokens = generate_msi_dao_impl_definition(&dao_struct_ident, fields, paths);
 
     quote! {
         #dao_struct_tokens
+        #getter_tokens
         #dao_impl_tokens
+        #check_constraints_tokens
+        #from_row_tokens
         #primary_identifier_impl_tokens
+        #conflicting_columns_tokens
+        #scoped_values_tokens
+        #key_tokens
         #msi_dao_impl_tokens
     }
 }
 
 fn generate_dao_struct_definition(
     dao_struct_ident: &Ident,
-    fields: &Vec<FieldInformation>,
+    fields: &[FieldInformation],
 ) -> TokenStream {
     // Pretty sure we could just append `fields` to the token stream for this but I want to
     // explicitly drop visibilities here so all properties are private.
@@ -46,22 +75,39 @@ fn generate_dao_struct_definition(
     }
     quote! {
 
-        #[derive(Clone, Debug, PartialEq, getset::Getters)]
-        #[getset(get = "pub")]
+        #[derive(Clone, Debug, PartialEq)]
         pub struct #dao_struct_ident {
             #field_tokens
         }
     }
 }
 
+fn generate_getters_for_dao(dao_struct_ident: &Ident, fields: &[FieldInformation]) -> TokenStream {
+    let field_idents = fields.iter().map(|f| f.ident().clone()).collect_vec();
+    let field_types = fields.iter().map(|f| f.ty.clone()).collect_vec();
+    quote! {
+        impl #dao_struct_ident {
+            #(
+                pub fn #field_idents(&self) -> &#field_types {
+                    &self.#field_idents
+                }
+            )*
+        }
+    }
+}
+
 fn generate_primary_identifier_impl_definition(
     primary_identifier: &Option<&FieldInformation>,
     dao_struct_ident: &Ident,
+    paths: &CratePaths,
 ) -> TokenStream {
+    let identifier = paths.identifier();
+    let to_identifier = paths.to_identifier();
+    let primary_identifier_trait = paths.primary_identifier();
     let dao_primary_identifier = match primary_identifier {
         Some(identifier) => {
             let identifier_ident = identifier.ident.clone();
-            quote! { Some( self.#identifier_ident.to_identifier() ) }
+            quote! { Some( #to_identifier::to_identifier(&self.#identifier_ident) ) }
         }
         None => {
             quote! { None }
@@ -69,8 +115,8 @@ fn generate_primary_identifier_impl_definition(
     };
 
     quote! {
-        impl PrimaryIdentifier for #dao_struct_ident {
-            fn primary_identifier(&self) -> Option<Identifier> {
+        impl #primary_identifier_trait for #dao_struct_ident {
+            fn primary_identifier(&self) -> ::core::option::Option<#identifier> {
                 #dao_primary_identifier
             }
         }
@@ -79,78 +125,350 @@ fn generate_primary_identifier_impl_definition(
 
 fn generate_msi_dao_impl_definition(
     dao_struct_ident: &Ident,
-    fields: &Vec<FieldInformation>,
+    fields: &[FieldInformation],
+    paths: &CratePaths,
 ) -> TokenStream {
-    let conflicts_definition_tokens = generate_msi_dao_conflicts_definition(fields);
-    let to_row_definition_tokens = generate_msi_dao_to_row_definition(fields);
+    let conflicts_definition_tokens = generate_msi_dao_conflicts_definition();
+    let to_row_definition_tokens = generate_msi_dao_to_row_definition(fields, paths);
+    let msi_dao = paths.msi_dao();
 
     quote! {
-        impl MsiDao for #dao_struct_ident {
+        impl #msi_dao for #dao_struct_ident {
             #conflicts_definition_tokens
             #to_row_definition_tokens
         }
     }
 }
 
-fn generate_msi_dao_conflicts_definition(fields: &Vec<FieldInformation>) -> TokenStream {
-    let mut conflict_expression = TokenStream::new();
-    // Get the fields that are marked as primary_key as these are what is used to check for
-    // conflicts.
-    for field in fields {
-        if !field.primary_key {
-            continue;
+fn generate_conflicting_columns_for_dao(
+    dao_struct_ident: &Ident,
+    unique_keys: &[Vec<&FieldInformation>],
+) -> TokenStream {
+    let checks = unique_keys.iter().map(|key| {
+        let column_names = key.iter().map(|field| field.column_name());
+        // Nullable columns only conflict when both rows hold a value.
+        let conditions = key.iter().map(|field| {
+            let field_ident = field.ident();
+            if field.is_nullable() {
+                quote! { (self.#field_ident.is_some() && self.#field_ident == other.#field_ident) }
+            } else {
+                quote! { self.#field_ident == other.#field_ident }
+            }
+        });
+        quote! {
+            if #(#conditions)&&* {
+                columns.extend([#(#column_names),*]);
+            }
+        }
+    });
+    let body = if unique_keys.is_empty() {
+        quote! {
+            let _ = other;
+            ::std::vec::Vec::new()
+        }
+    } else {
+        quote! {
+            let mut columns = ::std::vec::Vec::new();
+            #(#checks)*
+            columns
+        }
+    };
+    quote! {
+        impl #dao_struct_ident {
+            /// The columns that hold the same values in both rows where the table requires them to
+            /// be unique. Empty when both rows can be stored in the same table.
+            pub fn conflicting_columns(&self, other: &Self) -> ::std::vec::Vec<&'static str> {
+                #body
+            }
         }
+    }
+}
 
-        let and_and = if !conflict_expression.is_empty() {
-            quote!(&&)
-        } else {
-            TokenStream::default()
-        };
+/// The `*Key` type holding the values of every `primary_key` field and the `key()` accessor that
+/// creates it. Tables without a primary key have neither.
+fn generate_key_for_dao(
+    target_name: &str,
+    dao_struct_ident: &Ident,
+    fields: &[FieldInformation],
+) -> TokenStream {
+    let primary_key = fields
+        .iter()
+        .filter(|field| field.primary_key)
+        .collect_vec();
+    if primary_key.is_empty() {
+        return quote! {};
+    }
+    let key_ident = key_from_name(target_name);
+    let key_idents = primary_key.iter().map(|field| field.ident()).collect_vec();
+    let key_types = primary_key.iter().map(|field| &field.ty).collect_vec();
+    let key_comment = &format!(
+        "The primary key of a row of the `{target_name}` table. Holds the `primary_key` fields in \
+        the order they are declared."
+    );
+    // A single field is converted on its own rather than as a one element tuple.
+    let from_key = if let [key_type] = key_types.as_slice() {
+        quote! {
+            impl ::core::convert::From<#key_type> for #key_ident {
+                fn from(value: #key_type) -> Self {
+                    #key_ident(value)
+                }
+            }
+        }
+    } else {
+        let positions = (0..key_types.len()).map(syn::Index::from);
+        quote! {
+            impl ::core::convert::From<( #(#key_types),* )> for #key_ident {
+                fn from(value: ( #(#key_types),* )) -> Self {
+                    #key_ident( #(value.#positions),* )
+                }
+            }
+        }
+    };
+    quote! {
+        #[doc = #key_comment]
+        #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+        pub struct #key_ident( #(pub #key_types),* );
+
+        #from_key
 
-        let field_ident = &field.ident;
-        conflict_expression = quote! {
-            #conflict_expression
-            #and_and self.#field_ident == other.#field_ident
+        impl #dao_struct_ident {
+            /// The primary key of the row.
+            pub fn key(&self) -> #key_ident {
+                #key_ident( #(self.#key_idents.clone()),* )
+            }
+        }
+    }
+}
+
+fn generate_scoped_values_for_dao(
+    dao_struct_ident: &Ident,
+    fields: &[FieldInformation],
+    paths: &CratePaths,
+) -> TokenStream {
+    let scoped_fields = fields
+        .iter()
+        .filter(|field| field.unique_scope.is_some())
+        .collect_vec();
+    if scoped_fields.is_empty() {
+        return quote! {};
+    }
+    let msi = &paths.msi;
+    let values = scoped_fields.iter().map(|field| {
+        let scope = &field.unique_scope;
+        let column_name = field.column_name();
+        let value = generate_field_to_value(field, paths);
+        quote! { (#scope, #column_name, #value) }
+    });
+    quote! {
+        impl #dao_struct_ident {
+            /// The scope, column and value of every `unique_scope` column that holds a value.
+            pub fn scoped_values(&self) -> ::std::vec::Vec<(&'static str, &'static str, #msi::Value)> {
+                [#(#values),*]
+                    .into_iter()
+                    .filter(|(_, _, value)| !value.is_null())
+                    .collect()
+            }
         }
     }
+}
 
+fn generate_msi_dao_conflicts_definition() -> TokenStream {
     quote! {
         fn conflicts_with(&self, other: &Self) -> bool {
-            #conflict_expression
+            !self.conflicting_columns(other).is_empty()
         }
     }
 }
 
-fn generate_msi_dao_to_row_definition(fields: &Vec<FieldInformation>) -> TokenStream {
+fn generate_msi_dao_to_row_definition(
+    fields: &[FieldInformation],
+    paths: &CratePaths,
+) -> TokenStream {
+    let msi = &paths.msi;
     let mut fields_to_msi_value_tokens = TokenStream::new();
     for field in fields {
-        let field_ident = &field.ident;
+        let value = generate_field_to_value(field, paths);
         fields_to_msi_value_tokens = quote! {
             #fields_to_msi_value_tokens
-            msi::ToValue::to_value(&self.#field_ident),
+            #value,
         }
     }
 
     quote! {
-        fn to_row(&self) -> Vec<msi::Value> {
-            vec![
+        fn to_row(&self) -> ::std::vec::Vec<#msi::Value> {
+            ::std::vec![
                 #fields_to_msi_value_tokens
             ]
         }
     }
 }
 
-fn generate_new_for_dao(target_name: &str, fields: &[FieldInformation]) -> TokenStream {
-    let field_idents = fields
+/// The `msi::Value` of the field on `self`.
+fn generate_field_to_value(field: &FieldInformation, paths: &CratePaths) -> TokenStream {
+    let msi = &paths.msi;
+    let field_ident = field.ident();
+    // `None` is always written as a null value instead of relying on how the `Option` converts
+    // itself.
+    if field.is_nullable() {
+        quote! {
+            match &self.#field_ident {
+                Some(value) => #msi::ToValue::to_value(value),
+                None => #msi::Value::Null,
+            }
+        }
+    } else {
+        quote! { #msi::ToValue::to_value(&self.#field_ident) }
+    }
+}
+
+fn generate_check_constraints_for_dao(
+    target_name: &str,
+    dao_name: &Ident,
+    fields: &[FieldInformation],
+    paths: &CratePaths,
+) -> TokenStream {
+    let error_name = error_from_name(target_name);
+    let checks = fields.iter().filter_map(|field| {
+        let checks = generate_constraint_check_tokens(field, &error_name, paths);
+        if checks.is_empty() {
+            return None;
+        }
+        let value = generate_field_to_value(field, paths);
+        Some(quote! {
+            {
+                let value = #value;
+                #checks
+            }
+        })
+    });
+    quote! {
+        impl #dao_name {
+            /// Checks the value of every field against the `length`, `min`, `max` and `set` of its
+            /// column.
+            pub fn check_constraints(&self) -> ::core::result::Result<(), #error_name> {
+                #(#checks)*
+                Ok(())
+            }
+        }
+    }
+}
+
+fn generate_new_for_dao(
+    target_name: &str,
+    dao_name: &Ident,
+    fields: &[FieldInformation],
+) -> TokenStream {
+    let error_name = error_from_name(target_name);
+    let arguments = fields
         .iter()
-        .map(|f| f.ident.clone().expect("Field didn't have an identifier"))
+        .filter(|field| field.is_constructor_argument())
         .collect_vec();
-    let field_types = fields.iter().map(|f| f.ty.clone()).collect_vec();
-    let dao_name = dao_from_name(target_name);
+    let field_idents = arguments.iter().map(|f| f.ident().clone()).collect_vec();
+    let field_types = arguments.iter().map(|f| f.ty.clone()).collect_vec();
+    let filled_fields = fields.iter().filter_map(|field| {
+        let field_ident = field.ident();
+        let value = field.fill_expression(dao_name)?;
+        Some(quote! { #field_ident: #value, })
+    });
     quote! {
         impl #dao_name {
-            pub fn new( #(#field_idents: impl Into<#field_types>),* ) -> #dao_name {
-                #dao_name { #(#field_idents: #field_idents.into()),* }
+            /// Creates the row for callers whose values are already known to be valid. The values
+            /// are not checked against their columns, use `try_new` for that.
+            pub fn new( #(#field_idents: impl ::core::convert::Into<#field_types>),* ) -> #dao_name {
+                #dao_name {
+                    #(#field_idents: #field_idents.into(),)*
+                    #(#filled_fields)*
+                }
+            }
+
+            /// Creates the row after checking that every value fits in its column. See
+            /// `check_constraints`.
+            pub fn try_new(
+                #(#field_idents: impl ::core::convert::Into<#field_types>),*
+            ) -> ::core::result::Result<#dao_name, #error_name> {
+                let dao = Self::new( #(#field_idents),* );
+                dao.check_constraints()?;
+                Ok(dao)
+            }
+        }
+    }
+}
+
+fn generate_from_row_for_dao(
+    target_name: &str,
+    dao_name: &Ident,
+    fields: &[FieldInformation],
+    paths: &CratePaths,
+) -> TokenStream {
+    let msi = &paths.msi;
+    let error_name = error_from_name(target_name);
+
+    let field_tokens = fields.iter().map(|field| {
+        let field_ident = field.ident();
+        let column_name = field.column_name();
+        let convert = |ty: TokenStream| {
+            quote! {
+                match <#ty as ::core::convert::TryFrom<#msi::Value>>::try_from(value.clone()) {
+                    Ok(converted) => converted,
+                    Err(error) => {
+                        return Err(#error_name::InvalidValue {
+                            column: #column_name,
+                            value: value.to_string(),
+                            reason: ::std::string::ToString::to_string(&error),
+                        });
+                    }
+                }
+            }
+        };
+        let conversion = if field.is_nullable() {
+            // The inner type of an `Option` hidden behind an alias is inferred from the field.
+            let convert_inner = match option_inner_type(&field.ty) {
+                Some(inner_type) => convert(quote! { #inner_type }),
+                None => convert(quote! { _ }),
+            };
+            quote! {
+                if value.is_null() {
+                    None
+                } else {
+                    Some(#convert_inner)
+                }
+            }
+        } else {
+            let ty = &field.ty;
+            convert(quote! { #ty })
+        };
+        quote! {
+            #field_ident: {
+                if !row.has_column(#column_name) {
+                    return Err(#error_name::MissingColumn { column: #column_name });
+                }
+                let value = &row[#column_name];
+                #conversion
+            },
+        }
+    });
+
+    let from_row_comment = &format!(
+        "Reads a `{dao_name}` from a row of the `{target_name}` table. Columns are looked up by \
+        name so the order of the columns in the row does not matter."
+    );
+    quote! {
+        impl #dao_name {
+            #[doc = #from_row_comment]
+            pub fn from_row(row: &#msi::Row) -> ::core::result::Result<#dao_name, #error_name> {
+                let dao = #dao_name {
+                    #(#field_tokens)*
+                };
+                dao.check_constraints()?;
+                Ok(dao)
+            }
+        }
+
+        impl ::core::convert::TryFrom<#msi::Row> for #dao_name {
+            type Error = #error_name;
+
+            fn try_from(row: #msi::Row) -> ::core::result::Result<Self, Self::Error> {
+                Self::from_row(&row)
             }
         }
     }
@@ -0,0 +1,124 @@
+use crate::helper::*;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Ident;
+
+pub fn generate_error_tokens(target_name: &str, dao_name: &Ident) -> TokenStream {
+    let error_ident = error_from_name(target_name);
+
+    let error_comment = &format!(
+        "Errors produced while creating a `{dao_name}` or converting rows of the `{target_name}` \
+        table into one."
+    );
+    let missing_column_message = format!("Table `{target_name}` has no column `{{column}}`");
+    let missing_field_message = format!("Field `{{field}}` of `{dao_name}` was never set");
+    let invalid_value_message = format!(
+        "Failed to convert value `{{value}}` in column `{{column}}` of table `{target_name}`: {{reason}}"
+    );
+    let not_allowed_message = format!(
+        "Value `{{value}}` is not allowed in column `{{column}}` of table `{target_name}`: {{reason}}"
+    );
+    let too_long_message = format!(
+        "Value of field `{{field}}` is {{length}} characters long but column `{{column}}` of table \
+        `{target_name}` allows at most {{max_length}}"
+    );
+    let conflict_message = format!(
+        "Row conflicts with an existing row of table `{target_name}` in columns {{columns}}"
+    );
+    let scope_conflict_message = format!(
+        "Value `{{value}}` of column `{{column}}` in table `{target_name}` is already used in \
+        scope `{{scope}}` by table `{{table}}`"
+    );
+    let rejected_message = format!("Row was rejected by table `{target_name}`: {{reason}}");
+    quote! {
+        #[doc = #error_comment]
+        #[derive(Clone, Debug, PartialEq)]
+        pub enum #error_ident {
+            /// The row does not contain the column.
+            MissingColumn { column: &'static str },
+            /// A builder was finished without setting a field that is not nullable.
+            MissingField { field: &'static str },
+            /// The value stored in the column could not be converted into the type of the field.
+            InvalidValue {
+                column: &'static str,
+                value: ::std::string::String,
+                reason: ::std::string::String,
+            },
+            /// The value breaks the `min`, `max` or `set` constraint of the column.
+            NotAllowed {
+                column: &'static str,
+                value: ::std::string::String,
+                reason: ::std::string::String,
+            },
+            /// The value of the field is longer than the `length` of its column.
+            TooLong {
+                field: &'static str,
+                column: &'static str,
+                length: usize,
+                max_length: usize,
+            },
+            /// The row has the same values as a row already in the table in columns that must be
+            /// unique.
+            Conflict { columns: ::std::vec::Vec<&'static str> },
+            /// The value of a `unique_scope` column is already used by a row of a table that shares
+            /// the registry.
+            ScopeConflict {
+                column: &'static str,
+                scope: &'static str,
+                value: ::std::string::String,
+                table: &'static str,
+            },
+            /// An `on_insert` or `on_remove` function of the table refused the change.
+            Rejected { reason: ::std::string::String },
+        }
+
+        impl ::std::fmt::Display for #error_ident {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                match self {
+                    Self::MissingColumn { column } => {
+                        write!(f, #missing_column_message, column = column)
+                    }
+                    Self::MissingField { field } => {
+                        write!(f, #missing_field_message, field = field)
+                    }
+                    Self::InvalidValue { column, value, reason } => write!(
+                        f,
+                        #invalid_value_message,
+                        column = column,
+                        value = value,
+                        reason = reason
+                    ),
+                    Self::NotAllowed { column, value, reason } => write!(
+                        f,
+                        #not_allowed_message,
+                        column = column,
+                        value = value,
+                        reason = reason
+                    ),
+                    Self::TooLong { field, column, length, max_length } => write!(
+                        f,
+                        #too_long_message,
+                        field = field,
+                        column = column,
+                        length = length,
+                        max_length = max_length
+                    ),
+                    Self::Conflict { columns } => {
+                        write!(f, #conflict_message, columns = columns.join(", "))
+                    }
+                    Self::ScopeConflict { column, scope, value, table } => write!(
+                        f,
+                        #scope_conflict_message,
+                        column = column,
+                        scope = scope,
+                        value = value,
+                        table = table
+                    ),
+                    Self::Rejected { reason } => write!(f, #rejected_message, reason = reason),
+                }
+            }
+        }
+
+        impl ::std::error::Error for #error_ident {}
+    }
+}
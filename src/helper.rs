@@ -30,10 +30,31 @@ pub fn snake_case_to_pascal_case(s: &str) -> String {
         .unwrap_or_else(|_| panic!("Failed to capitalize string: [{}]", s))
 }
 
+/// The inverse of `snake_case_to_pascal_case`. EX: "FeatureComponents" becomes
+/// "feature_components".
+pub fn pascal_case_to_snake_case(s: &str) -> String {
+    let mut snake = String::new();
+    for (index, c) in s.chars().enumerate() {
+        if c.is_uppercase() && index > 0 {
+            snake.push('_');
+        }
+        snake.extend(c.to_lowercase());
+    }
+    snake
+}
+
+pub fn container_from_name(target_name: &str) -> Ident {
+    format_ident!("{target_name}{CONTAINER_SUFFIX}")
+}
+
 pub fn dao_from_name(target_name: &str) -> Ident {
     format_ident!("{target_name}{DAO_SUFFIX}")
 }
 
+pub fn error_from_name(target_name: &str) -> Ident {
+    format_ident!("{target_name}{ERROR_SUFFIX}")
+}
+
 pub fn table_from_name(target_name: &str) -> Ident {
     format_ident!("{target_name}{TABLE_SUFFIX}")
 }
@@ -42,7 +63,37 @@ pub fn identifier_from_name(target_name: &str) -> Ident {
     format_ident!("{target_name}{IDENTIFIER_SUFFIX}")
 }
 
+pub fn key_from_name(target_name: &str) -> Ident {
+    format_ident!("{target_name}{KEY_SUFFIX}")
+}
+
+pub fn kind_from_name(target_name: &str) -> Ident {
+    format_ident!("{target_name}{KIND_SUFFIX}")
+}
+
+pub fn registry_from_name(target_name: &str) -> Ident {
+    format_ident!("{target_name}{REGISTRY_SUFFIX}")
+}
+
 pub fn identifier_generator_from_name(target_name: &str) -> Ident {
     let identifier = identifier_from_name(target_name);
     format_ident!("{identifier}{GENERATOR_SUFFIX}")
 }
+
+/// Returns `T` if the given type is written as `Option<T>`.
+pub fn option_inner_type(ty: &syn::Type) -> Option<&syn::Type> {
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+    match arguments.args.iter().exactly_one() {
+        Ok(syn::GenericArgument::Type(inner)) => Some(inner),
+        _ => None,
+    }
+}
@@ -1,19 +1,30 @@
-use crate::{constants::*, helper::*, msi_tables::FieldInformation};
+use crate::{constants::*, helper::*, msi_tables::FieldInformation, paths::CratePaths};
 use proc_macro2::TokenStream;
 use quote::quote;
 
 pub fn generate_identifier_tokens(
     target_name: &str,
     primary_identifier: &FieldInformation,
+    paths: &CratePaths,
 ) -> TokenStream {
-    let identifier_impl_tokens = generate_identifier_definition(target_name);
+    let identifier_impl_tokens = generate_identifier_definition(target_name, paths);
+    let generator_tokens = if primary_identifier.is_generated_identifier() {
+        generate_identifier_generator_definition(target_name, paths)
+    } else {
+        Default::default()
+    };
     quote! {
         #identifier_impl_tokens
+        #generator_tokens
     }
 }
 
-fn generate_identifier_definition(target_name: &str) -> TokenStream {
+fn generate_identifier_definition(target_name: &str, paths: &CratePaths) -> TokenStream {
     let new_identifier_ident = identifier_from_name(target_name);
+    let msi = &paths.msi;
+    let macros = &paths.macros;
+    let identifier = paths.identifier();
+    let to_identifier = paths.to_identifier();
 
     let identifier_comment = &format!(
         "This is a simple wrapper around `Identifier` for the `{target_name}{TABLE_SUFFIX}`. \
@@ -21,20 +32,84 @@ fn generate_identifier_definition(target_name: &str) -> TokenStream {
     );
     quote! {
         #[doc = #identifier_comment]
-        #[derive(Clone, Debug, Default, PartialEq, derive_more::Display, whimsi_macros::IdentifierToValue)]
-        pub struct #new_identifier_ident(Identifier);
+        #[derive(Clone, Debug, Default, PartialEq, Eq, Hash, #macros::IdentifierToValue)]
+        pub struct #new_identifier_ident(#identifier);
 
-        impl ToIdentifier for #new_identifier_ident {
-            fn to_identifier(&self) -> Identifier {
+        impl ::std::fmt::Display for #new_identifier_ident {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                ::std::fmt::Display::fmt(&self.0, f)
+            }
+        }
+
+        impl #to_identifier for #new_identifier_ident {
+            fn to_identifier(&self) -> #identifier {
                 self.0
             }
         }
 
-        impl std::str::FromStr for #new_identifier_ident {
-            type Err = anyhow::Error;
+        impl ::std::str::FromStr for #new_identifier_ident {
+            type Err = <#identifier as ::std::str::FromStr>::Err;
+
+            fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                <#identifier as ::std::str::FromStr>::from_str(s).map(Self)
+            }
+        }
+
+        impl ::core::convert::TryFrom<#msi::Value> for #new_identifier_ident {
+            type Error = <#identifier as ::core::convert::TryFrom<#msi::Value>>::Error;
+
+            fn try_from(value: #msi::Value) -> ::core::result::Result<Self, Self::Error> {
+                <#identifier as ::core::convert::TryFrom<#msi::Value>>::try_from(value).map(Self)
+            }
+        }
+    }
+}
+
+fn generate_identifier_generator_definition(target_name: &str, paths: &CratePaths) -> TokenStream {
+    let identifier_generator = paths.identifier_generator();
+    let identifier = paths.identifier();
+    let identifier_ident = identifier_from_name(target_name);
+    let generator_ident = identifier_generator_from_name(target_name);
+    let id_prefix = target_name.to_uppercase();
+
+    let generator_comment = &format!(
+        "Creates new `{identifier_ident}`s for the `{target_name}{TABLE_SUFFIX}`. Identifiers \
+        are prefixed with `{id_prefix}` and never collide with an identifier in the shared `used` \
+        registry."
+    );
+    quote! {
+        #[doc = #generator_comment]
+        #[derive(Clone, Debug, Default, PartialEq)]
+        pub struct #generator_ident {
+            count: usize,
+            // A reference to a vec of all used Identifiers that should not be generated again.
+            // These are all identifiers that inhabit a primary_key column.
+            used: ::std::rc::Rc<::std::cell::RefCell<::std::vec::Vec<#identifier>>>,
+        }
+
+        impl #identifier_generator for #generator_ident {
+            type IdentifierType = #identifier_ident;
+
+            fn id_prefix(&self) -> &str {
+                #id_prefix
+            }
+
+            fn used(&self) -> &::std::rc::Rc<::std::cell::RefCell<::std::vec::Vec<#identifier>>> {
+                &self.used
+            }
+
+            fn count(&self) -> usize {
+                self.count
+            }
+
+            fn count_mut(&mut self) -> &mut usize {
+                &mut self.count
+            }
+        }
 
-            fn from_str(s: &str) -> anyhow::Result<Self> {
-                Ok(Self(Identifier::from_str(s)?))
+        impl ::core::convert::From<::std::rc::Rc<::std::cell::RefCell<::std::vec::Vec<#identifier>>>> for #generator_ident {
+            fn from(used: ::std::rc::Rc<::std::cell::RefCell<::std::vec::Vec<#identifier>>>) -> Self {
+                Self { count: 0, used }
             }
         }
     }
@@ -16,12 +16,19 @@ use syn::parse_macro_input;
 
 extern crate proc_macro;
 
+pub(crate) mod builder;
 pub(crate) mod constants;
+pub(crate) mod constraint;
+pub(crate) mod container;
 pub(crate) mod dao;
+pub(crate) mod error;
 pub(crate) mod helper;
 pub(crate) mod identifier;
+pub(crate) mod lookup;
 mod msi_tables;
+pub(crate) mod paths;
 pub(crate) mod table;
+pub(crate) mod validation;
 
 #[proc_macro]
 pub fn msi_table_list(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
@@ -35,3 +42,10 @@ pub fn msi_table_list(input: proc_macro::TokenStream) -> proc_macro::TokenStream
 pub fn msi_table(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     msi_table_list(input)
 }
+
+/// Derives the table for a struct that is used as the DAO. The generated `Table`, `Identifier` and
+/// trait impls are added next to the struct, which is left untouched.
+#[proc_macro_derive(MsiTable, attributes(msi_table, msi_column))]
+pub fn derive_msi_table(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    msi_tables::gen_derive_impl(input.into()).into()
+}
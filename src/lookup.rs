@@ -0,0 +1,192 @@
+use itertools::Itertools;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::Ident;
+
+use crate::{helper::*, msi_tables::FieldInformation};
+
+/// The fields that rows can be found by with `find_by_<field>`: every foreign key and every
+/// `indexed` field.
+pub fn lookup_fields(fields: &[FieldInformation]) -> Vec<&FieldInformation> {
+    fields
+        .iter()
+        .filter(|field| field.foreign_key().is_some() || field.indexed.is_present())
+        .collect_vec()
+}
+
+/// The fields that the table keeps a secondary index for.
+pub fn indexed_fields(fields: &[FieldInformation]) -> Vec<&FieldInformation> {
+    fields
+        .iter()
+        .filter(|field| field.indexed.is_present())
+        .collect_vec()
+}
+
+/// The name of the table field that holds the secondary index of `field`.
+pub fn index_ident(field: &FieldInformation) -> Ident {
+    format_ident!("index_by_{}", field.ident())
+}
+
+/// The type that `field` is looked up by. Nullable fields are looked up by the inner type, which
+/// is named through `IntoIterator` when the field is an alias of `Option`.
+fn lookup_type(field: &FieldInformation) -> syn::Type {
+    let ty = &field.ty;
+    match option_inner_type(ty) {
+        Some(inner_type) if field.is_nullable() => inner_type.clone(),
+        None if field.is_nullable() => {
+            syn::parse_quote!(<#ty as ::core::iter::IntoIterator>::Item)
+        }
+        _ => ty.clone(),
+    }
+}
+
+/// An `Option<&T>` of the value of `field` in `entry`. Null values are never looked up.
+pub fn lookup_value(field: &FieldInformation, entry: TokenStream) -> TokenStream {
+    let field_ident = field.ident();
+    if field.is_nullable() {
+        quote! { #entry.#field_ident.as_ref() }
+    } else {
+        quote! { Some(&#entry.#field_ident) }
+    }
+}
+
+/// The secondary index of every `indexed` field as fields of the table. Like the key index they
+/// are `None` until they are needed after the entries were changed directly.
+pub fn generate_index_fields(fields: &[FieldInformation]) -> TokenStream {
+    let indexed = indexed_fields(fields);
+    let index_idents = indexed.iter().map(|field| index_ident(field));
+    let index_types = indexed.iter().map(|field| lookup_type(field));
+    quote! {
+        #(
+            #index_idents: ::core::option::Option<::std::collections::HashMap<#index_types, ::std::vec::Vec<usize>>>,
+        )*
+    }
+}
+
+/// `find_by_<field>` for every lookup field and the methods that keep the secondary indexes in
+/// sync with the entries.
+pub fn generate_lookup_tokens(dao_name: &Ident, fields: &[FieldInformation]) -> TokenStream {
+    let find_tokens = lookup_fields(fields).into_iter().map(|field| {
+        let find_ident = format_ident!("find_by_{}", field.ident());
+        let lookup_type = lookup_type(field);
+        let value = lookup_value(field, quote! { entry });
+        let find_comment = &format!(
+            "The entries whose `{}` column holds `value`.",
+            field.column_name()
+        );
+        if !field.indexed.is_present() {
+            return quote! {
+                #[doc = #find_comment]
+                pub fn #find_ident<'a>(
+                    &'a self,
+                    value: &'a #lookup_type,
+                ) -> impl ::core::iter::Iterator<Item = &'a #dao_name> + 'a {
+                    self.entries
+                        .iter()
+                        .filter(move |entry| #value == Some(value))
+                }
+            };
+        }
+        let index_ident = index_ident(field);
+        quote! {
+            #[doc = #find_comment]
+            pub fn #find_ident<'a>(
+                &'a self,
+                value: &'a #lookup_type,
+            ) -> impl ::core::iter::Iterator<Item = &'a #dao_name> + 'a {
+                // Every entry is compared while the index is dropped.
+                let indexed = self.#index_ident.as_ref().map(|index| {
+                    index
+                        .get(value)
+                        .into_iter()
+                        .flatten()
+                        .map(|position| &self.entries[*position])
+                });
+                let scanned = indexed.is_none().then(|| {
+                    self.entries
+                        .iter()
+                        .filter(move |entry| #value == Some(value))
+                });
+                indexed
+                    .into_iter()
+                    .flatten()
+                    .chain(scanned.into_iter().flatten())
+            }
+        }
+    });
+
+    let indexed = indexed_fields(fields);
+    if indexed.is_empty() {
+        return quote! { #(#find_tokens)* };
+    }
+    let index_idents = indexed.iter().map(|field| index_ident(field)).collect_vec();
+    let entry_values = indexed
+        .iter()
+        .map(|field| lookup_value(field, quote! { entries[position] }))
+        .collect_vec();
+    let build_values = indexed
+        .iter()
+        .map(|field| lookup_value(field, quote! { entry }))
+        .collect_vec();
+    quote! {
+        #(#find_tokens)*
+
+        /// Adds the entry at `position` to every secondary index. Indexes that were dropped are
+        /// rebuilt.
+        fn index_entry(&mut self, position: usize) {
+            let entries = &self.entries;
+            #(
+                let index = self.#index_idents.get_or_insert_with(|| {
+                    let mut index = ::std::collections::HashMap::<_, ::std::vec::Vec<usize>>::new();
+                    for (position, entry) in entries.iter().enumerate() {
+                        if let Some(value) = #build_values {
+                            index.entry(value.clone()).or_default().push(position);
+                        }
+                    }
+                    index
+                });
+                if let Some(value) = #entry_values {
+                    let positions = index.entry(value.clone()).or_default();
+                    if let Err(insert_at) = positions.binary_search(&position) {
+                        positions.insert(insert_at, position);
+                    }
+                }
+            )*
+        }
+
+        /// Removes the entry at `position` from every secondary index before its values change.
+        fn unindex_entry(&mut self, position: usize) {
+            let entries = &self.entries;
+            #(
+                if let (Some(index), Some(value)) = (&mut self.#index_idents, #entry_values) {
+                    if let Some(positions) = index.get_mut(value) {
+                        positions.retain(|indexed| *indexed != position);
+                    }
+                }
+            )*
+        }
+
+        /// Moves every entry after `removed` one position down in the secondary indexes after the
+        /// entry at `removed` was taken out of `entries`.
+        fn remove_from_indexes(&mut self, removed: usize) {
+            #(
+                if let Some(index) = &mut self.#index_idents {
+                    index.retain(|_, positions| {
+                        positions.retain(|position| *position != removed);
+                        for position in positions.iter_mut() {
+                            if *position > removed {
+                                *position -= 1;
+                            }
+                        }
+                        !positions.is_empty()
+                    });
+                }
+            )*
+        }
+
+        /// Drops every secondary index so it is rebuilt when it is next needed.
+        fn drop_indexes(&mut self) {
+            #( self.#index_idents = None; )*
+        }
+    }
+}
@@ -2,15 +2,25 @@ use darling::{FromDeriveInput, FromField, FromVariant};
 use itertools::Itertools;
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
-use syn::{self};
+use std::str::FromStr;
+use syn::{self, spanned::Spanned};
 
 use crate::{
-    dao::generate_dao_tokens, helper::*, identifier::generate_identifier_tokens,
+    builder::generate_builder_tokens,
+    constants::*,
+    constraint::{ColumnConstraints, SetValue},
+    container::generate_container_tokens,
+    dao::generate_dao_tokens,
+    error::generate_error_tokens,
+    helper::*,
+    identifier::generate_identifier_tokens,
+    paths::CratePaths,
     table::generate_table_tokens,
+    validation::generate_validation_tokens,
 };
 
 #[derive(FromDeriveInput, Clone)]
-#[darling(attributes(msi_table))]
+#[darling(attributes(msi_table), supports(struct_named, enum_named))]
 pub(crate) struct DeriveInformation {
     pub ident: syn::Ident,
     pub data: darling::ast::Data<VariantInformation, FieldInformation>,
@@ -20,35 +30,71 @@ pub(crate) struct DeriveInformation {
     // If this is a struct, the base name of the table to create. EX: "Directory" will produces
     // struct names such as "DirectoryDao" and "DirectoryTable".
     pub name: Option<String>,
+
+    // Overrides for the paths of the crates used by the generated code. See `CratePaths`.
+    #[darling(default, rename = "crate")]
+    pub krate: Option<syn::Path>,
+    #[darling(default)]
+    pub msi: Option<syn::Path>,
+    #[darling(default)]
+    pub macros: Option<syn::Path>,
+
+    // WARN: ONLY USED IF DERIVED ITEM IS A `struct`! Tables in an enum take these from the
+    // `msi_table` attribute on their variant.
+    #[darling(flatten)]
+    pub options: TableOptions,
 }
 
 #[derive(FromVariant, Clone)]
+#[darling(attributes(msi_table))]
 pub(crate) struct VariantInformation {
     pub ident: syn::Ident,
     pub fields: darling::ast::Fields<FieldInformation>,
+
+    #[darling(flatten)]
+    pub options: TableOptions,
+}
+
+/// Options that apply to a whole table.
+#[derive(darling::FromMeta, Clone, Default)]
+pub(crate) struct TableOptions {
+    // Groups of fields whose values together must be unique in the table. EX: `unique(a, b)`.
+    #[darling(default, multiple)]
+    pub unique: Vec<darling::util::PathList>,
+
+    // Functions called by the generated `insert` and `remove` of the table. `on_insert` receives
+    // the table and the new row and may change the row or refuse it by returning an error.
+    // `on_remove` receives the table and the removed row and may refuse the removal.
+    #[darling(default)]
+    pub on_insert: Option<syn::Path>,
+    #[darling(default)]
+    pub on_remove: Option<syn::Path>,
 }
 
 #[derive(FromField, Clone)]
-#[darling(attributes(msi_column))]
+#[darling(attributes(msi_column), forward_attrs(doc))]
 pub(crate) struct FieldInformation {
     // -- Builtins ------------------------------------------------------------
     // Field name
     pub ident: Option<syn::Ident>,
     // Type of the field
     pub ty: syn::Type,
+    // Doc comments on the field. Used as the description of the column.
+    pub attrs: Vec<syn::Attribute>,
 
     // -- Custom --------------------------------------------------------------
-    // The category that the given column will be converted to when placed in the table.
-    pub category: syn::Expr,
+    // The category that the given column will be converted to when placed in the table. If it is
+    // not provided it is inferred from the name of the field's type.
+    #[darling(default)]
+    pub category: Option<syn::Expr>,
 
     // The maximum length of the string placed in the column. This is specific to each table so I
     // can't abstract it away. If it is not provided a default based on the provided Category is
     // used.
     //
-    // NOTE: I considered making this optional and using sane defaults for columns
-    // based on the given category but I like the idea of not obscuring what values
-    // are being used for a given column. This is only optional for categories of Integer and
-    // DoubleInteger.
+    // NOTE: Only categories with a length that is the same in every table have a default, see
+    // `DEFAULT_COLUMN_LENGTHS`. Every other string column must define its length so the value
+    // used for the column is never obscured. Integer and DoubleInteger columns never have one.
     pub length: Option<syn::Expr>,
 
     // What the name of the column is. If it is not provided the identifier of the field is
@@ -64,9 +110,53 @@ pub(crate) struct FieldInformation {
     #[darling(default, rename = "identifier")]
     pub identifier_options: Option<IdentifierInformation>,
 
-    // Whether or not the given field is localizable as specified in the MSI documentation.
+    // Whether or not the given field is localizable as specified in the MSI documentation. Kept as
+    // a `Flag` so misuse can be reported at the attribute.
+    #[darling(default)]
+    pub localizable: darling::util::Flag,
+
+    // Overrides whether the column accepts null values. Without either of these the column is
+    // nullable when the field is written as `Option<T>`. Needed for type aliases of `Option`, which
+    // are then read and written like any other `Option`.
+    #[darling(default)]
+    pub nullable: darling::util::Flag,
+    #[darling(default)]
+    pub not_null: darling::util::Flag,
+
+    // Inclusive bounds of the values that may be stored in an integer column.
+    #[darling(default)]
+    pub min: Option<syn::Expr>,
+    #[darling(default)]
+    pub max: Option<syn::Expr>,
+
+    // The only values that may be stored in the column, e.g. `set = [0, 1, 2, 3]`.
+    #[darling(default)]
+    pub set: Option<syn::Expr>,
+
+    // Expression that gives the value of the column when a row is created. The column is left out
+    // of `new` and the builder.
+    #[darling(default)]
+    pub default: Option<syn::Expr>,
+
+    // Function that gives the value of the column when a row is created. A bare `generated` calls
+    // `generate_<field>()` on the DAO, which the user implements. Like `default` the column is left
+    // out of `new` and the builder.
     #[darling(default)]
-    pub localizable: bool,
+    pub generated: Option<darling::util::Override<syn::Path>>,
+
+    // Whether no two rows of the table may have the same value in this column.
+    #[darling(default)]
+    pub unique: darling::util::Flag,
+
+    // Name of a scope whose values must be unique across every table of an `msi_table_list!` that
+    // uses it, e.g. `unique_scope = "Keys"`. Checked through the registry generated for the list.
+    #[darling(default)]
+    pub unique_scope: Option<syn::LitStr>,
+
+    // Whether the table keeps an index of the values of this column so `find_by_<field>` does not
+    // compare every row. Foreign keys can always be found by, other columns only when indexed.
+    #[darling(default)]
+    pub indexed: darling::util::Flag,
 }
 
 #[derive(darling::FromMeta, FromField, Clone)]
@@ -78,109 +168,852 @@ pub(crate) struct IdentifierInformation {
     // Denotes if the given identifier is a foreign key into the table and if it is, what table the
     // key is from.
     #[darling(default)]
-    pub foreign_key: Option<String>,
+    pub foreign_key: Option<syn::LitStr>,
+}
+
+/// The tables defined in a single invocation of the macro. Used to resolve references from one
+/// table into another.
+pub(crate) struct TableList<'a> {
+    tables: Vec<(String, &'a [FieldInformation])>,
+    // When set, every table that is referenced must be defined in this list. This is only the case
+    // for `msi_table_list!` as single tables are expected to reference tables defined elsewhere.
+    complete: bool,
+    // The registry shared by the tables of an `msi_table_list!` to check `unique_scope` columns.
+    registry: Option<syn::Ident>,
+}
+
+impl FieldInformation {
+    /// The identifier of the field.
+    ///
+    /// Every field has one as tuple structs and tuple variants are rejected while parsing.
+    pub fn ident(&self) -> &syn::Ident {
+        self.ident
+            .as_ref()
+            .expect("Unnamed fields are rejected by `DeriveInformation`")
+    }
+
+    /// Whether this field is the identifier that rows of this table are referenced by.
+    pub fn is_primary_identifier(&self) -> bool {
+        self.primary_key
+            && self
+                .identifier_options
+                .as_ref()
+                .is_some_and(|options| options.foreign_key.is_none())
+    }
+
+    /// Whether this field is the identifier of the table and new values for it should be created
+    /// by a generator.
+    pub fn is_generated_identifier(&self) -> bool {
+        self.is_primary_identifier()
+            && self
+                .identifier_options
+                .as_ref()
+                .is_some_and(|options| options.generated)
+    }
+
+    /// The table this field references, if it is a foreign key.
+    pub fn foreign_key(&self) -> Option<&syn::LitStr> {
+        self.identifier_options.as_ref()?.foreign_key.as_ref()
+    }
+
+    /// The doc comment of the field joined into a single line.
+    pub fn description(&self) -> Option<String> {
+        let lines = self
+            .attrs
+            .iter()
+            .filter_map(|attr| match &attr.meta {
+                syn::Meta::NameValue(syn::MetaNameValue {
+                    value:
+                        syn::Expr::Lit(syn::ExprLit {
+                            lit: syn::Lit::Str(line),
+                            ..
+                        }),
+                    ..
+                }) if attr.path().is_ident("doc") => Some(line.value().trim().to_string()),
+                _ => None,
+            })
+            .filter(|line| !line.is_empty())
+            .collect_vec();
+        (!lines.is_empty()).then(|| lines.join(" "))
+    }
+
+    /// The expression that fills the column when a row is created, if the caller does not pass the
+    /// value in.
+    pub fn fill_expression(&self, dao_name: &syn::Ident) -> Option<TokenStream> {
+        if let Some(default) = &self.default {
+            return Some(quote! { #default });
+        }
+        match self.generated.as_ref()? {
+            darling::util::Override::Explicit(function) => Some(quote! { #function() }),
+            darling::util::Override::Inherit => {
+                let function = format_ident!("generate_{}", self.ident());
+                Some(quote! { #dao_name::#function() })
+            }
+        }
+    }
+
+    /// Whether the value of the column is passed to `new` and the builder.
+    pub fn is_constructor_argument(&self) -> bool {
+        self.default.is_none() && self.generated.is_none()
+    }
+
+    /// Whether the column accepts null values.
+    pub fn is_nullable(&self) -> bool {
+        if self.nullable.is_present() {
+            true
+        } else if self.not_null.is_present() {
+            false
+        } else {
+            option_inner_type(&self.ty).is_some()
+        }
+    }
+
+    /// The name of the column in the MSI table.
+    pub fn column_name(&self) -> String {
+        self.column_name
+            .clone()
+            .unwrap_or_else(|| snake_case_to_pascal_case(&self.ident().to_string()))
+    }
+
+    /// The name of the `msi::Category` variant of the column. Taken from the `category` attribute
+    /// when given, otherwise inferred from the last segment of the field's type.
+    pub fn category_variant(&self) -> darling::Result<syn::Ident> {
+        let Some(category) = &self.category else {
+            return self.inferred_category_variant();
+        };
+        let syn::Expr::Path(path) = category else {
+            return Err(darling::Error::custom(
+                "Category must be a path to a `msi::Category` variant",
+            )
+            .with_span(category));
+        };
+        let Some(category_segment) = path.path.segments.last() else {
+            return Err(darling::Error::custom("Path contains no segments").with_span(path));
+        };
+        Ok(category_segment.ident.clone())
+    }
+
+    /// Infers the category from type names such as `DefaultDir`, `DirectoryIdentifier`, `i16` and
+    /// `Option<i32>`.
+    fn inferred_category_variant(&self) -> darling::Result<syn::Ident> {
+        let ty = option_inner_type(&self.ty).unwrap_or(&self.ty);
+        let cannot_infer = || {
+            darling::Error::custom(format!(
+                "Cannot infer the category of field `{}` from its type. Add \
+                `category = msi::Category::...` to its `msi_column` attribute",
+                self.ident()
+            ))
+            .with_span(ty)
+        };
+        let syn::Type::Path(path) = ty else {
+            return Err(cannot_infer());
+        };
+        let segment = path.path.segments.last().ok_or_else(cannot_infer)?;
+        let type_name = segment.ident.to_string();
+        let variant = match type_name.as_str() {
+            "i16" => "Integer",
+            "i32" => "DoubleInteger",
+            name if msi::Category::from_str(name).is_ok() => name,
+            name if name.ends_with(IDENTIFIER_SUFFIX) => "Identifier",
+            _ => return Err(cannot_infer()),
+        };
+        Ok(syn::Ident::new(variant, segment.ident.span()))
+    }
+
+    /// The length of the string column. Taken from the `length` attribute when given, otherwise
+    /// the length that the MSI schema uses for every column of the category, e.g. 38 for GUIDs.
+    pub fn column_length(&self) -> Option<syn::Expr> {
+        if let Some(length) = &self.length {
+            return Some(length.clone());
+        }
+        let category = self.msi_category().ok()?;
+        let (_, length) = DEFAULT_COLUMN_LENGTHS
+            .iter()
+            .find(|(default_category, _)| *default_category == category)?;
+        let length = proc_macro2::Literal::u32_unsuffixed(*length);
+        Some(syn::parse_quote!(#length))
+    }
+
+    /// The `msi::Category` of the column.
+    pub fn msi_category(&self) -> darling::Result<msi::Category> {
+        let variant = self.category_variant()?;
+        msi::Category::from_str(&variant.to_string()).map_err(|_| {
+            darling::Error::custom(format!("Category is invalid: {variant}")).with_span(&variant)
+        })
+    }
+
+    /// Rejects attribute combinations that would produce a column msiexec refuses to load.
+    pub fn validate(&self, category: msi::Category) -> darling::Result<()> {
+        let mut errors = darling::Error::accumulator();
+        let ident = self.ident();
+        let is_integer = matches!(
+            category,
+            msi::Category::Integer | msi::Category::DoubleInteger
+        );
+
+        if self.localizable.is_present() && is_integer {
+            errors.push(
+                darling::Error::custom(format!(
+                    "Field `{ident}` with category {category} cannot be localizable"
+                ))
+                .with_span(&self.localizable.span()),
+            );
+        }
+
+        if let Some(length) = &self.length {
+            if is_integer {
+                errors.push(
+                    darling::Error::custom(format!(
+                        "Field `{ident}` with category {category} cannot define a length. \
+                        Integer columns are sized by their category"
+                    ))
+                    .with_span(length),
+                );
+            } else if !length_in_range(length) {
+                errors.push(
+                    darling::Error::custom(format!(
+                        "Length of field `{ident}` must be between 0 and {MAX_COLUMN_LENGTH}"
+                    ))
+                    .with_span(length),
+                );
+            }
+        }
+
+        if self.identifier_options.is_some() && category != msi::Category::Identifier {
+            errors.push(
+                darling::Error::custom(format!(
+                    "Field `{ident}` is an identifier but has category {category}. Identifiers \
+                    must use the Identifier category"
+                ))
+                .with_span(&match &self.category {
+                    Some(category) => category.span(),
+                    None => self.ty.span(),
+                }),
+            );
+        }
+
+        if self.nullable.is_present() && self.not_null.is_present() {
+            errors.push(
+                darling::Error::custom(format!(
+                    "Field `{ident}` cannot be both `nullable` and `not_null`"
+                ))
+                .with_span(&self.not_null.span()),
+            );
+        } else if self.not_null.is_present() && option_inner_type(&self.ty).is_some() {
+            errors.push(
+                darling::Error::custom(format!(
+                    "Field `{ident}` is marked `not_null` but its type is an `Option`"
+                ))
+                .with_span(&self.not_null.span()),
+            );
+        }
+
+        if let Some(constraints) = errors.handle(ColumnConstraints::parse(self)) {
+            self.validate_constraints(&constraints, is_integer, &mut errors);
+        }
+
+        if self.default.is_some() && self.generated.is_some() {
+            errors.push(
+                darling::Error::custom(format!(
+                    "Field `{ident}` cannot have both a `default` and be `generated`"
+                ))
+                .with_span(ident),
+            );
+        }
+        if self.is_generated_identifier() && !self.is_constructor_argument() {
+            errors.push(
+                darling::Error::custom(format!(
+                    "Field `{ident}` is created by the identifier generator and cannot have a \
+                    `default` or be `generated`"
+                ))
+                .with_span(ident),
+            );
+        }
+
+        if self.primary_key && self.is_nullable() {
+            errors.push(
+                darling::Error::custom(format!(
+                    "Field `{ident}` is a primary key and cannot be nullable"
+                ))
+                .with_span(&self.ty),
+            );
+        }
+
+        errors.finish()
+    }
+
+    /// Checks that `min`, `max` and `set` fit the category of the column.
+    fn validate_constraints(
+        &self,
+        constraints: &ColumnConstraints,
+        is_integer: bool,
+        errors: &mut darling::error::Accumulator,
+    ) {
+        let ident = self.ident();
+        for bound in [&self.min, &self.max].into_iter().flatten() {
+            if !is_integer {
+                errors.push(
+                    darling::Error::custom(format!(
+                        "Field `{ident}` is not an integer column and cannot define `min` or `max`"
+                    ))
+                    .with_span(bound),
+                );
+            }
+        }
+        if let (Some(min), Some(max)) = (constraints.min, constraints.max)
+            && min > max
+        {
+            errors.push(
+                darling::Error::custom(format!(
+                    "`min` of field `{ident}` is greater than its `max`"
+                ))
+                .with_span(self.min.as_ref().unwrap()),
+            );
+        }
+        if let (Some(set), Some(set_expr)) = (&constraints.set, &self.set) {
+            let expected_int = |value: &SetValue| matches!(value, SetValue::Int(_)) == is_integer;
+            if !set.iter().all(expected_int) {
+                let expected = if is_integer { "integers" } else { "strings" };
+                errors.push(
+                    darling::Error::custom(format!(
+                        "`set` of field `{ident}` must only contain {expected}"
+                    ))
+                    .with_span(set_expr),
+                );
+            }
+        }
+    }
+}
+
+/// Whether a literal `length` fits in an MSI string column. Lengths that are not integer literals
+/// cannot be checked while expanding the macro and are accepted.
+fn length_in_range(length: &syn::Expr) -> bool {
+    match length {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(int),
+            ..
+        }) => int
+            .base10_parse::<u32>()
+            .is_ok_and(|length| length <= MAX_COLUMN_LENGTH),
+        syn::Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) => !matches!(**expr, syn::Expr::Lit(_)),
+        _ => true,
+    }
+}
+
+impl<'a> TableList<'a> {
+    /// The field of the table that `foreign_key` references. `None` if the table is not defined
+    /// in an incomplete list.
+    pub fn referenced_field(
+        &self,
+        foreign_key: &syn::LitStr,
+    ) -> darling::Result<Option<&'a FieldInformation>> {
+        let index = self.foreign_key_index(foreign_key)?;
+        Ok(self
+            .tables
+            .iter()
+            .find(|(name, _)| *name == foreign_key.value())
+            .map(|(_, fields)| &fields[index]))
+    }
+
+    /// The registry shared by the tables in the list, if they have one.
+    pub fn registry(&self) -> Option<&syn::Ident> {
+        self.registry.as_ref()
+    }
+
+    /// Index of the column that other tables reference when they use `foreign_key` to point at
+    /// the given table.
+    ///
+    /// Tables that are not defined in an incomplete list are assumed to be referenced by their
+    /// first column.
+    pub fn foreign_key_index(&self, foreign_key: &syn::LitStr) -> darling::Result<usize> {
+        let table_name = foreign_key.value();
+        let Some((_, fields)) = self.tables.iter().find(|(name, _)| *name == table_name) else {
+            if self.complete {
+                return Err(darling::Error::custom(format!(
+                    "Foreign key references table `{table_name}` which is not defined in this list"
+                ))
+                .with_span(foreign_key));
+            }
+            return Ok(0);
+        };
+
+        // Prefer the table's own identifier. Tables without one are referenced by the first
+        // column of their primary key.
+        fields
+            .iter()
+            .position(FieldInformation::is_primary_identifier)
+            .or_else(|| fields.iter().position(|field| field.primary_key))
+            .ok_or_else(|| {
+                darling::Error::custom(format!(
+                    "Foreign key references table `{table_name}` which has no primary key"
+                ))
+                .with_span(foreign_key)
+            })
+    }
 }
 
 pub fn gen_tables_impl(input: TokenStream) -> TokenStream {
-    let input = syn::parse2::<syn::DeriveInput>(input).unwrap();
-    let derive_input =
-        DeriveInformation::from_derive_input(&input).expect("Failed to parse derive input");
+    try_gen_tables_impl(input).unwrap_or_else(darling::Error::write_errors)
+}
+
+fn try_gen_tables_impl(input: TokenStream) -> darling::Result<TokenStream> {
+    let input = syn::parse2::<syn::DeriveInput>(input)?;
+    let derive_input = DeriveInformation::from_derive_input(&input)?;
+    let paths = CratePaths::new(derive_input.krate, derive_input.msi, derive_input.macros);
 
-    let output_tokens = match derive_input.data {
+    match derive_input.data {
         darling::ast::Data::Enum(items) => {
-            gen_tables_for_enum(&derive_input.ident.to_string(), items)
+            gen_tables_for_enum(&derive_input.ident.to_string(), items, &paths)
         }
         darling::ast::Data::Struct(fields) => {
             let name = capitalize(&derive_input.name.unwrap_or(derive_input.ident.to_string()));
-            gen_tables_for_fields(&name, fields.fields)
+            let tables = TableList {
+                tables: vec![(name.clone(), &fields.fields)],
+                complete: false,
+                registry: None,
+            };
+            gen_tables_for_fields(
+                &name,
+                derive_input.ident.span(),
+                &fields.fields,
+                &derive_input.options,
+                &tables,
+                &paths,
+                None,
+            )
         }
-    };
+    }
+}
 
-    quote! {
-        use whimsi_lib::types::column::identifier::Identifier;
-        use whimsi_lib::types::column::identifier::ToIdentifier;
+pub fn gen_derive_impl(input: TokenStream) -> TokenStream {
+    try_gen_derive_impl(input).unwrap_or_else(darling::Error::write_errors)
+}
 
-        #output_tokens
+/// `#[derive(MsiTable)]` keeps the user's struct as the DAO and generates everything else next to
+/// it. The table name defaults to the struct name with any `Dao` suffix removed.
+fn try_gen_derive_impl(input: TokenStream) -> darling::Result<TokenStream> {
+    let input = syn::parse2::<syn::DeriveInput>(input)?;
+    if !input.generics.params.is_empty() {
+        return Err(
+            darling::Error::custom("`MsiTable` cannot be derived for generic structs")
+                .with_span(&input.generics),
+        );
     }
+    let derive_input = DeriveInformation::from_derive_input(&input)?;
+    let paths = CratePaths::new(derive_input.krate, derive_input.msi, derive_input.macros);
+
+    let darling::ast::Data::Struct(fields) = derive_input.data else {
+        return Err(
+            darling::Error::custom("`MsiTable` can only be derived for structs. Use `msi_table_list!` to define several tables at once.")
+                .with_span(&derive_input.ident),
+        );
+    };
+    let name = capitalize(&derive_input.name.unwrap_or_else(|| {
+        let ident = derive_input.ident.to_string();
+        ident
+            .strip_suffix(DAO_SUFFIX)
+            .filter(|name| !name.is_empty())
+            .unwrap_or(&ident)
+            .to_string()
+    }));
+    let tables = TableList {
+        tables: vec![(name.clone(), &fields.fields)],
+        complete: false,
+        registry: None,
+    };
+    gen_tables_for_fields(
+        &name,
+        derive_input.ident.span(),
+        &fields.fields,
+        &derive_input.options,
+        &tables,
+        &paths,
+        Some(&derive_input.ident),
+    )
 }
 
-fn gen_tables_for_enum(name: &str, items: Vec<VariantInformation>) -> TokenStream {
-    let (struct_variants, dao_variants) = items
+fn gen_tables_for_enum(
+    name: &str,
+    items: Vec<VariantInformation>,
+    paths: &CratePaths,
+) -> darling::Result<TokenStream> {
+    let msi = &paths.msi;
+    let table_enum_name = format_ident!("{name}");
+    let dao_enum_name = dao_from_name(name);
+    let kind_name = kind_from_name(name);
+    let registry_name = registry_from_name(name);
+    let variants = items.iter().map(|v| v.ident.clone()).collect_vec();
+    let table_names = variants
         .iter()
-        .map(|v| {
-            let variant = v.ident.clone();
-            let table_name = table_from_name(&variant.to_string());
-            let dao_name = dao_from_name(&variant.to_string());
-            (
-                quote! { #variant ( #table_name ) , },
-                quote! { #variant ( #dao_name ) , },
-            )
-        })
-        .collect::<(Vec<TokenStream>, Vec<TokenStream>)>();
+        .map(|variant| table_from_name(&variant.to_string()))
+        .collect_vec();
+    let dao_names = variants
+        .iter()
+        .map(|variant| dao_from_name(&variant.to_string()))
+        .collect_vec();
+    let variant_names = variants.iter().map(|variant| variant.to_string());
 
     // Generate the enum containing all of the variant structs
-    let table_enum_name = format_ident!("{name}");
-    let dao_enum_name = dao_from_name(name);
     let tokens = quote! {
-        #[derive(Clone, PartialEq, strum::EnumDiscriminants, derive_more::Into, derive_more::From, derive_more::TryFrom, derive_more::TryInto, strum::Display)]
-        #[strum_discriminants(name(MsiTable))]
+        #[derive(Clone, PartialEq)]
         pub enum #table_enum_name {
-            #(#struct_variants)*
+            #( #variants ( #table_names ) , )*
         }
 
-        #[derive(Clone, PartialEq)]
+        /// The kind of table stored in each variant.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum #kind_name {
+            #( #variants , )*
+        }
+
+        impl ::core::convert::From<&#table_enum_name> for #kind_name {
+            fn from(value: &#table_enum_name) -> Self {
+                match value {
+                    #( #table_enum_name::#variants(_) => #kind_name::#variants , )*
+                }
+            }
+        }
+
+        impl ::std::fmt::Display for #table_enum_name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                match self {
+                    #( #table_enum_name::#variants(_) => f.write_str(#variant_names) , )*
+                }
+            }
+        }
+
+        #(
+            impl ::core::convert::From<#table_names> for #table_enum_name {
+                fn from(value: #table_names) -> Self {
+                    #table_enum_name::#variants(value)
+                }
+            }
+
+            impl ::core::convert::TryFrom<#table_enum_name> for #table_names {
+                type Error = #table_enum_name;
+
+                fn try_from(value: #table_enum_name) -> ::core::result::Result<Self, Self::Error> {
+                    match value {
+                        #table_enum_name::#variants(table) => Ok(table),
+                        #[allow(unreachable_patterns)]
+                        other => Err(other),
+                    }
+                }
+            }
+        )*
+
+        impl #table_enum_name {
+            /// The rows of the `_Validation` table that describe every table in this list.
+            pub fn validation_rows() -> ::std::vec::Vec<::std::vec::Vec<#msi::Value>> {
+                let mut rows = ::std::vec::Vec::new();
+                #( rows.extend(#table_names::validation_rows()); )*
+                rows
+            }
+        }
+
+        #[derive(Clone, Debug, PartialEq)]
         pub enum #dao_enum_name {
-            #(#dao_variants)*
+            #( #variants ( #dao_names ) , )*
+        }
+
+        /// The values of `unique_scope` columns used by the rows of every table that shares this
+        /// registry. Share one registry between the tables with `with_registry` so a value can only
+        /// be used once in each scope across all of them.
+        #[derive(Clone, Debug, Default, PartialEq)]
+        pub struct #registry_name {
+            values: ::std::collections::HashMap<(&'static str, #msi::Value), &'static str>,
         }
+
+        impl #registry_name {
+            /// The name of the table whose row already uses `value` in `scope`.
+            pub fn owner(&self, scope: &'static str, value: &#msi::Value) -> ::core::option::Option<&'static str> {
+                self.values.get(&(scope, value.clone())).copied()
+            }
+
+            /// Records that a row of `table` uses `value` in `scope`. Returns the table that already
+            /// uses the value instead if there is one.
+            pub fn register(
+                &mut self,
+                scope: &'static str,
+                value: #msi::Value,
+                table: &'static str,
+            ) -> ::core::result::Result<(), &'static str> {
+                match self.values.entry((scope, value)) {
+                    ::std::collections::hash_map::Entry::Occupied(entry) => Err(*entry.get()),
+                    ::std::collections::hash_map::Entry::Vacant(entry) => {
+                        entry.insert(table);
+                        Ok(())
+                    }
+                }
+            }
+
+            /// Frees `value` in `scope` so another row may use it.
+            pub fn unregister(&mut self, scope: &'static str, value: &#msi::Value) {
+                self.values.remove(&(scope, value.clone()));
+            }
+        }
+    };
+
+    let tables = TableList {
+        tables: items
+            .iter()
+            .map(|variant| {
+                (
+                    capitalize(&variant.ident.to_string()),
+                    variant.fields.fields.as_slice(),
+                )
+            })
+            .collect(),
+        complete: true,
+        registry: Some(registry_name.clone()),
     };
-    items.iter().fold(tokens, |acc, variant| {
-        let table_def_tokens =
-            gen_tables_for_fields(&variant.ident.to_string(), variant.fields.fields.clone());
+
+    let container_tokens = generate_container_tokens(name, &items, &tables, paths);
+    let tokens = quote! {
+        #tokens
+        #container_tokens
+    };
+
+    // Every variant is generated even if an earlier one failed so that all of the problems in the
+    // definition are reported at once.
+    let mut errors = darling::Error::accumulator();
+    let tokens = items.iter().fold(tokens, |acc, variant| {
+        let table_def_tokens = errors.handle(gen_tables_for_fields(
+            &variant.ident.to_string(),
+            variant.ident.span(),
+            &variant.fields.fields,
+            &variant.options,
+            &tables,
+            paths,
+            None,
+        ));
         quote! {
             #acc
             #table_def_tokens
         }
-    })
+    });
+    errors.finish_with(tokens)
 }
 
-fn gen_tables_for_fields(base_name: &str, fields: Vec<FieldInformation>) -> TokenStream {
+/// Generates everything for a single table. `user_dao` is the struct the user wrote when it should
+/// be used as the DAO instead of generating one.
+fn gen_tables_for_fields(
+    base_name: &str,
+    name_span: proc_macro2::Span,
+    fields: &[FieldInformation],
+    options: &TableOptions,
+    tables: &TableList,
+    paths: &CratePaths,
+    user_dao: Option<&syn::Ident>,
+) -> darling::Result<TokenStream> {
     let target_name = capitalize(base_name);
+    let dao_name = user_dao
+        .cloned()
+        .unwrap_or_else(|| dao_from_name(&target_name));
+    let mut errors = darling::Error::accumulator();
+
+    errors.handle(validate_table_structure(&target_name, name_span, fields));
 
     // Create the table-specific identifier if one should be made. These are made when a table has
     // a column with a type that implements `ToIdentifier` and the column is not marked as a
     // foreign key.
-    let primary_identifier = fields
+    let primary_identifiers = fields
         .iter()
-        .filter(|f| {
-            f.primary_key
-                && f.identifier_options.is_some()
-                && f.identifier_options.clone().unwrap().foreign_key.is_none()
-        })
-        .at_most_one()
-        .unwrap_or_else(|_| {
-            panic!(
-                "More than one field marked as primary identifier found in defintion. This is not supported."
+        .filter(|f| f.is_primary_identifier())
+        .collect_vec();
+    for extra_identifier in primary_identifiers.iter().skip(1) {
+        errors.push(
+            darling::Error::custom(
+                "More than one field marked as primary identifier found in definition. This is not supported.",
             )
-        });
+            .with_span(extra_identifier.ident()),
+        );
+    }
+    let primary_identifier = primary_identifiers.first().copied();
 
     let identifier_tokens = if let Some(primary_identifier) = primary_identifier {
-        generate_identifier_tokens(&target_name, primary_identifier)
+        generate_identifier_tokens(&target_name, primary_identifier, paths)
     } else {
         Default::default()
     };
 
-    let dao_tokens = generate_dao_tokens(&target_name, &primary_identifier, &fields);
+    let error_tokens = generate_error_tokens(&target_name, &dao_name);
+
+    let unique_keys = errors
+        .handle(unique_keys(fields, options))
+        .unwrap_or_default();
+
+    // Generated DAOs get a getter for every field which would clash with `key()`.
+    if user_dao.is_none()
+        && fields.iter().any(|field| field.primary_key)
+        && let Some(field) = fields.iter().find(|field| field.ident() == "key")
+    {
+        errors.push(
+            darling::Error::custom(
+                "Field `key` clashes with the generated `key()` accessor. Rename the field and \
+                    set `column_name` instead",
+            )
+            .with_span(field.ident()),
+        );
+    }
+
+    if tables.registry().is_none() {
+        for scope in fields
+            .iter()
+            .filter_map(|field| field.unique_scope.as_ref())
+        {
+            errors.push(
+                darling::Error::custom(
+                    "`unique_scope` is only supported for tables defined in `msi_table_list!`",
+                )
+                .with_span(scope),
+            );
+        }
+    }
+
+    let dao_tokens = generate_dao_tokens(
+        &target_name,
+        user_dao,
+        &primary_identifier,
+        fields,
+        &unique_keys,
+        paths,
+    );
+
+    let table_tokens = errors.handle(generate_table_tokens(
+        &target_name,
+        &dao_name,
+        fields,
+        options,
+        tables,
+        paths,
+    ));
 
-    let table_tokens = generate_table_tokens(&target_name, &fields);
+    let builder_tokens = generate_builder_tokens(&target_name, &dao_name, fields);
+
+    let validation_tokens = generate_validation_tokens(&target_name, fields, tables, paths);
 
     // Generate the DAO code.
     let output_tokens = quote! {
         #identifier_tokens
+        #error_tokens
         #dao_tokens
+        #builder_tokens
         #table_tokens
+        #validation_tokens
     };
 
-    output_tokens
+    errors.finish_with(output_tokens)
+}
+
+/// The groups of fields whose values together may only appear once in the table: the primary key,
+/// every `unique` column and every `unique(...)` group of the table.
+fn unique_keys<'a>(
+    fields: &'a [FieldInformation],
+    options: &TableOptions,
+) -> darling::Result<Vec<Vec<&'a FieldInformation>>> {
+    let mut errors = darling::Error::accumulator();
+    let mut keys = Vec::new();
+
+    let primary_key = fields
+        .iter()
+        .filter(|field| field.primary_key)
+        .collect_vec();
+    if !primary_key.is_empty() {
+        keys.push(primary_key);
+    }
+    keys.extend(
+        fields
+            .iter()
+            .filter(|field| field.unique.is_present())
+            .map(|field| vec![field]),
+    );
+    for group in &options.unique {
+        let group_fields = group
+            .iter()
+            .filter_map(|path| {
+                let field = fields
+                    .iter()
+                    .find(|field| path.is_ident(field.ident()))
+                    .ok_or_else(|| {
+                        darling::Error::custom("`unique` must only list fields of the table")
+                            .with_span(path)
+                    });
+                errors.handle(field)
+            })
+            .collect_vec();
+        keys.push(group_fields);
+    }
+
+    errors.finish_with(keys)
+}
+
+/// Checks the limits MSI places on the shape of a table. Attribute combinations of single columns
+/// are checked by `FieldInformation::validate`.
+fn validate_table_structure(
+    table_name: &str,
+    name_span: proc_macro2::Span,
+    fields: &[FieldInformation],
+) -> darling::Result<()> {
+    let mut errors = darling::Error::accumulator();
+
+    if table_name.len() > MAX_TABLE_NAME_LENGTH {
+        errors.push(
+            darling::Error::custom(format!(
+                "Table name `{table_name}` is longer than {MAX_TABLE_NAME_LENGTH} characters"
+            ))
+            .with_span(&name_span),
+        );
+    }
+    if RESERVED_TABLE_NAMES.contains(&table_name) {
+        errors.push(
+            darling::Error::custom(format!(
+                "Table name `{table_name}` is reserved by the MSI database"
+            ))
+            .with_span(&name_span),
+        );
+    }
+
+    if let Some(extra_field) = fields.get(MAX_COLUMNS) {
+        errors.push(
+            darling::Error::custom(format!(
+                "Table `{table_name}` has {} columns but at most {MAX_COLUMNS} are allowed",
+                fields.len()
+            ))
+            .with_span(extra_field.ident()),
+        );
+    }
+
+    let mut seen_column = false;
+    for field in fields {
+        let column_name = field.column_name();
+        if column_name.len() > MAX_COLUMN_NAME_LENGTH {
+            errors.push(
+                darling::Error::custom(format!(
+                    "Column name `{column_name}` is longer than {MAX_COLUMN_NAME_LENGTH} characters"
+                ))
+                .with_span(field.ident()),
+            );
+        }
+
+        if !field.primary_key {
+            seen_column = true;
+        } else if seen_column {
+            errors.push(
+                darling::Error::custom(format!(
+                    "Primary key column `{column_name}` must come before every column that is not \
+                    part of the primary key"
+                ))
+                .with_span(field.ident()),
+            );
+        }
+    }
+
+    errors.finish()
 }
 
 #[cfg(test)]
@@ -21,129 +21,651 @@ fn test_msi_table_with_generated_identifier() {
     let output = msi_tables::gen_tables_impl(input);
 
     let expected_output = quote! {
-        use whimsi_lib::types::column::identifier::Identifier;
-        use whimsi_lib::types::column::identifier::ToIdentifier;
-        use whimsi_lib::types::helpers::id_generator::IdentifierGenerator;
-
         #[doc = "This is a simple wrapper around `Identifier` for the `DirectoryTable`. Used to ensure that identifiers for the `DirectoryTable` are only used in valid locations."]
-        pub struct DirectoryIdentifier(Identifier);
+        #[derive(Clone, Debug, Default, PartialEq, Eq, Hash, ::whimsi_macros::IdentifierToValue)]
+        pub struct DirectoryIdentifier(::whimsi_lib::types::column::identifier::Identifier);
 
-        impl ToIdentifier for DirectoryIdentifier {
-            fn to_identifier(&self) -> Identifier {
-                self.0
-            }
+        impl ::std::fmt::Display for DirectoryIdentifier {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) ->::std::fmt::Result { ::std::fmt::Display::fmt(&self.0, f) }
+        }
+
+        impl ::whimsi_lib::types::column::identifier::ToIdentifier for DirectoryIdentifier {
+            fn to_identifier(&self) ->::whimsi_lib::types::column::identifier::Identifier { self.0 }
         }
 
-        impl std::str::FromStr for DirectoryIdentifier {
-            type Err = anyhow::Error;
+        impl ::std::str::FromStr for DirectoryIdentifier {
+            type Err = <::whimsi_lib::types::column::identifier::Identifier as ::std::str::FromStr>::Err;
+            fn from_str(s: &str) ->::core::result::Result<Self, Self::Err> {
+                <::whimsi_lib::types::column::identifier::Identifier as ::std::str::FromStr>::from_str(s).map(Self)
+            }
+        }
 
-            fn from_str(s: &str) -> anyhow::Result<Self> {
-                Ok(Self(Identifier::from_str(s)?))
+        impl ::core::convert::TryFrom<::msi::Value> for DirectoryIdentifier {
+            type Error = <::whimsi_lib::types::column::identifier::Identifier as ::core::convert::TryFrom<::msi::Value>>::Error;
+            fn try_from(value: ::msi::Value) ->::core::result::Result<Self, Self::Error> {
+                <::whimsi_lib::types::column::identifier::Identifier as ::core::convert::TryFrom<::msi::Value>>::try_from(value).map(Self)
             }
         }
 
-        #[derive(Debug, Clone, Default, PartialEq)]
-        pub(crate) struct DirectoryIdentifierGenerator {
+        #[doc = "Creates new `DirectoryIdentifier`s for the `DirectoryTable`. Identifiers are prefixed with `DIRECTORY` and never collide with an identifier in the shared `used` registry."]
+        #[derive(Clone, Debug, Default, PartialEq)]
+        pub struct DirectoryIdentifierGenerator {
             count: usize,
-            // A reference to a vec of all used Identifiers that should not be generated again.
-            // These are all identifiers that inhabit a primary_key column.
-            used: std::rc::Rc<std::cell::RefCell<Vec<Identifier>>>,
+            used: ::std::rc::Rc<::std::cell::RefCell<::std::vec::Vec<::whimsi_lib::types::column::identifier::Identifier>>>,
         }
 
-        impl IdentifierGenerator for DirectoryIdentifierGenerator {
+        impl ::whimsi_lib::types::helpers::id_generator::IdentifierGenerator for DirectoryIdentifierGenerator {
             type IdentifierType = DirectoryIdentifier;
-
-            fn id_prefix(&self) -> &str {
-                "DIRECTORY"
-            }
-
-            fn used(&self) -> &std::rc::Rc<std::cell::RefCell<Vec<Identifier>>> {
+            fn id_prefix(&self) -> &str { "DIRECTORY" }
+            fn used(&self) -> & ::std::rc::Rc<::std::cell::RefCell<::std::vec::Vec<::whimsi_lib::types::column::identifier::Identifier>>> {
                 &self.used
             }
+            fn count(&self) -> usize { self.count }
+            fn count_mut(&mut self) -> &mut usize { &mut self.count }
+        }
 
-            fn count(&self) -> usize {
-                self.count
+        impl ::core::convert::From<::std::rc::Rc<::std::cell::RefCell<::std::vec::Vec<::whimsi_lib::types::column::identifier::Identifier>>>> for DirectoryIdentifierGenerator {
+            fn from(used: ::std::rc::Rc<::std::cell::RefCell<::std::vec::Vec<::whimsi_lib::types::column::identifier::Identifier>>>) -> Self {
+                Self { count: 0, used }
             }
+        }
 
-            fn count_mut(&mut self) -> &mut usize {
-                &mut self.count
-            }
+        #[doc = "Errors produced while creating a `DirectoryDao` or converting rows of the `Directory` table into one."]
+        #[derive(Clone, Debug, PartialEq)]
+        pub enum DirectoryError {
+            #[doc = r" The row does not contain the column."]
+            MissingColumn { column: &'static str },
+            #[doc = r" A builder was finished without setting a field that is not nullable."]
+            MissingField { field: &'static str },
+            #[doc = r" The value stored in the column could not be converted into the type of the field."]
+            InvalidValue {
+                column: &'static str,
+                value: ::std::string::String,
+                reason: ::std::string::String,
+            },
+            #[doc = r" The value breaks the `min`, `max` or `set` constraint of the column."]
+            NotAllowed {
+                column: &'static str,
+                value: ::std::string::String,
+                reason: ::std::string::String,
+            },
+            #[doc = r" The value of the field is longer than the `length` of its column."]
+            TooLong {
+                field: &'static str,
+                column: &'static str,
+                length: usize,
+                max_length: usize,
+            },
+            #[doc = r" The row has the same values as a row already in the table in columns that must be"]
+            #[doc = r" unique."]
+            Conflict { columns: ::std::vec::Vec< &'static str> },
+            #[doc = r" The value of a `unique_scope` column is already used by a row of a table that shares"]
+            #[doc = r" the registry."]
+            ScopeConflict {
+                column: &'static str,
+                scope: &'static str,
+                value: ::std::string::String,
+                table: &'static str,
+            },
+            #[doc = r" An `on_insert` or `on_remove` function of the table refused the change."]
+            Rejected { reason: ::std::string::String },
         }
 
-        impl From<std::rc::Rc<std::cell::RefCell<Vec<Identifier>>>> for DirectoryIdentifierGenerator {
-            fn from(value: std::rc::Rc<std::cell::RefCell<Vec<Identifier>>>) -> Self {
-                let count = value.borrow().len();
-                Self {
-                    used: value,
-                    count: 0,
+        impl ::std::fmt::Display for DirectoryError {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) ->::std::fmt::Result {
+                match self {
+                    Self::MissingColumn { column } => {
+                        write!(f, "Table `Directory` has no column `{column}`", column = column)
+                    }
+                    Self::MissingField { field } => {
+                        write!(f, "Field `{field}` of `DirectoryDao` was never set", field = field)
+                    }
+                    Self::InvalidValue { column, value, reason } => write!(
+                        f,
+                        "Failed to convert value `{value}` in column `{column}` of table `Directory`: {reason}",
+                        column = column,
+                        value = value,
+                        reason = reason
+                    ),
+                    Self::NotAllowed { column, value, reason } => write!(
+                        f,
+                        "Value `{value}` is not allowed in column `{column}` of table `Directory`: {reason}",
+                        column = column,
+                        value = value,
+                        reason = reason
+                    ),
+                    Self::TooLong { field, column, length, max_length } => write!(
+                        f,
+                        "Value of field `{field}` is {length} characters long but column `{column}` of table `Directory` allows at most {max_length}",
+                        field = field,
+                        column = column,
+                        length = length,
+                        max_length = max_length
+                    ),
+                    Self::Conflict { columns } => {
+                        write!(
+                            f,
+                            "Row conflicts with an existing row of table `Directory` in columns {columns}",
+                            columns = columns.join(", ")
+                        )
+                    }
+                    Self::ScopeConflict { column, scope, value, table } => write!(
+                        f,
+                        "Value `{value}` of column `{column}` in table `Directory` is already used in scope `{scope}` by table `{table}`",
+                        column = column,
+                        scope = scope,
+                        value = value,
+                        table = table
+                    ),
+                    Self::Rejected { reason } => write!(
+                        f,
+                        "Row was rejected by table `Directory`: {reason}",
+                        reason = reason
+                    ),
                 }
             }
         }
 
+        impl ::std::error::Error for DirectoryError {}
+
+        #[derive(Clone, Debug, PartialEq)]
         pub struct DirectoryDao {
             directory: DirectoryIdentifier,
             parent_directory: Option<DirectoryIdentifier>,
             default_dir: DefaultDir,
         }
 
-        impl PrimaryIdentifier for DirectoryDao {
-            fn primary_identifier(&self) -> Option<Identifier> {
-                Some( self.directory.to_identifier() )
+        impl DirectoryDao {
+            pub fn directory(&self) -> &DirectoryIdentifier { &self.directory }
+            pub fn parent_directory(&self) -> &Option<DirectoryIdentifier> {
+                &self.parent_directory
+            }
+            pub fn default_dir(&self) -> &DefaultDir { &self.default_dir }
+        }
+
+        impl DirectoryDao {
+            #[doc = r" Creates the row for callers whose values are already known to be valid. The values"]
+            #[doc = r" are not checked against their columns, use `try_new` for that."]
+            pub fn new(
+                directory: impl ::core::convert::Into<DirectoryIdentifier>,
+                parent_directory: impl ::core::convert::Into<Option<DirectoryIdentifier>>,
+                default_dir: impl ::core::convert::Into<DefaultDir>
+            ) -> DirectoryDao {
+                DirectoryDao {
+                    directory: directory.into(),
+                    parent_directory: parent_directory.into(),
+                    default_dir: default_dir.into(),
+                }
+            }
+
+            #[doc = r" Creates the row after checking that every value fits in its column. See"]
+            #[doc = r" `check_constraints`."]
+            pub fn try_new(
+                directory: impl ::core::convert::Into<DirectoryIdentifier>,
+                parent_directory: impl ::core::convert::Into<Option<DirectoryIdentifier>>,
+                default_dir: impl ::core::convert::Into<DefaultDir>
+            ) ->::core::result::Result<DirectoryDao, DirectoryError> {
+                let dao = Self::new(directory, parent_directory, default_dir);
+                dao.check_constraints()?;
+                Ok(dao)
+            }
+        }
+
+        impl DirectoryDao {
+            #[doc = r" Checks the value of every field against the `length`, `min`, `max` and `set` of its"]
+            #[doc = r" column."]
+            pub fn check_constraints(&self) ->::core::result::Result<(), DirectoryError> {
+                {
+                    let value = ::msi::ToValue::to_value(&self.directory);
+                    if let ::msi::Value::Str(text) = &value {
+                        let max_length = (72) as usize;
+                        let length = text.chars().count();
+                        if max_length != 0 && length > max_length {
+                            return Err(DirectoryError::TooLong {
+                                field: "directory",
+                                column: "Directory",
+                                length,
+                                max_length,
+                            });
+                        }
+                    }
+                }
+                {
+                    let value = match &self.parent_directory {
+                        Some(value) =>::msi::ToValue::to_value(value),
+                        None =>::msi::Value::Null,
+                    };
+                    if let ::msi::Value::Str(text) = &value {
+                        let max_length = (72) as usize;
+                        let length = text.chars().count();
+                        if max_length != 0 && length > max_length {
+                            return Err(DirectoryError::TooLong {
+                                field: "parent_directory",
+                                column: "Directory_Parent",
+                                length,
+                                max_length,
+                            });
+                        }
+                    }
+                }
+                {
+                    let value = ::msi::ToValue::to_value(&self.default_dir);
+                    if let ::msi::Value::Str(text) = &value {
+                        let max_length = (255) as usize;
+                        let length = text.chars().count();
+                        if max_length != 0 && length > max_length {
+                            return Err(DirectoryError::TooLong {
+                                field: "default_dir",
+                                column: "DefaultDir",
+                                length,
+                                max_length,
+                            });
+                        }
+                    }
+                }
+                Ok(())
+            }
+        }
+
+        impl DirectoryDao {
+            #[doc = "Reads a `DirectoryDao` from a row of the `Directory` table. Columns are looked up by name so the order of the columns in the row does not matter."]
+            pub fn from_row(row: & ::msi::Row) ->::core::result::Result<DirectoryDao, DirectoryError> {
+                let dao = DirectoryDao {
+                    directory: {
+                        if !row.has_column("Directory") {
+                            return Err(DirectoryError::MissingColumn { column: "Directory" });
+                        }
+                        let value = &row["Directory"];
+                        match <DirectoryIdentifier as ::core::convert::TryFrom<::msi::Value>>::try_from(value.clone()) {
+                            Ok(converted) => converted,
+                            Err(error) => {
+                                return Err(DirectoryError::InvalidValue {
+                                    column: "Directory",
+                                    value: value.to_string(),
+                                    reason: ::std::string::ToString::to_string(&error),
+                                });
+                            }
+                        }
+                    },
+                    parent_directory: {
+                        if !row.has_column("Directory_Parent") {
+                            return Err(DirectoryError::MissingColumn { column: "Directory_Parent" });
+                        }
+                        let value = &row["Directory_Parent"];
+                        if value.is_null() {
+                            None
+                        } else {
+                            Some(match <DirectoryIdentifier as ::core::convert::TryFrom<::msi::Value>>::try_from(value.clone()) {
+                                Ok(converted) => converted,
+                                Err(error) => {
+                                    return Err(DirectoryError::InvalidValue {
+                                        column: "Directory_Parent",
+                                        value: value.to_string(),
+                                        reason: ::std::string::ToString::to_string(&error),
+                                    });
+                                }
+                            })
+                        }
+                    },
+                    default_dir: {
+                        if !row.has_column("DefaultDir") {
+                            return Err(DirectoryError::MissingColumn { column: "DefaultDir" });
+                        }
+                        let value = &row["DefaultDir"];
+                        match <DefaultDir as ::core::convert::TryFrom<::msi::Value>>::try_from(value.clone()) {
+                            Ok(converted) => converted,
+                            Err(error) => {
+                                return Err(DirectoryError::InvalidValue {
+                                    column: "DefaultDir",
+                                    value: value.to_string(),
+                                    reason: ::std::string::ToString::to_string(&error),
+                                });
+                            }
+                        }
+                    },
+                };
+                dao.check_constraints()?;
+                Ok(dao)
+            }
+        }
+
+        impl ::core::convert::TryFrom<::msi::Row> for DirectoryDao {
+            type Error = DirectoryError;
+            fn try_from(row: ::msi::Row) ->::core::result::Result<Self, Self::Error> {
+                Self::from_row(&row)
             }
         }
 
-        impl MsiDao for DirectoryDao {
+        impl ::whimsi_lib::tables::PrimaryIdentifier for DirectoryDao {
+            fn primary_identifier(&self) ->::core::option::Option<::whimsi_lib::types::column::identifier::Identifier> {
+                Some(::whimsi_lib::types::column::identifier::ToIdentifier::to_identifier(&self.directory))
+            }
+        }
 
-            fn conflicts_with(&self, other: &Self) -> bool {
-                self.directory == other.directory
+        impl DirectoryDao {
+            #[doc = r" The columns that hold the same values in both rows where the table requires them to"]
+            #[doc = r" be unique. Empty when both rows can be stored in the same table."]
+            pub fn conflicting_columns(&self, other: &Self) ->::std::vec::Vec< &'static str> {
+                let mut columns = ::std::vec::Vec::new();
+                if self.directory == other.directory {
+                    columns.extend(["Directory"]);
+                }
+                columns
             }
+        }
 
-            fn to_row(&self) -> Vec<msi::Value> {
-                vec![
-                    msi::ToValue::to_value(self.directory),
-                    msi::ToValue::to_value(self.parent_directory),
-                    msi::ToValue::to_value(self.default_dir),
+        #[doc = "The primary key of a row of the `Directory` table. Holds the `primary_key` fields in the order they are declared."]
+        #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+        pub struct DirectoryKey(pub DirectoryIdentifier);
+
+        impl ::core::convert::From<DirectoryIdentifier> for DirectoryKey {
+            fn from(value: DirectoryIdentifier) -> Self { DirectoryKey(value) }
+        }
+
+        impl DirectoryDao {
+            #[doc = r" The primary key of the row."]
+            pub fn key(&self) -> DirectoryKey { DirectoryKey(self.directory.clone()) }
+        }
+
+        impl ::whimsi_lib::tables::MsiDao for DirectoryDao {
+            fn conflicts_with(&self, other: &Self) -> bool { !self.conflicting_columns(other).is_empty() }
+            fn to_row(&self) ->::std::vec::Vec<::msi::Value> {
+                ::std::vec![
+                    ::msi::ToValue::to_value(&self.directory),
+                    match &self.parent_directory {
+                        Some(value) =>::msi::ToValue::to_value(value),
+                        None =>::msi::Value::Null,
+                    },
+                    ::msi::ToValue::to_value(&self.default_dir),
                 ]
             }
         }
 
+        #[doc = "Builds a `DirectoryDao` one named column at a time. Create one with `DirectoryDao::builder`."]
+        #[derive(Clone, Debug, Default)]
+        pub struct DirectoryDaoBuilder {
+            directory: ::core::option::Option<DirectoryIdentifier>,
+            parent_directory: Option<DirectoryIdentifier>,
+            default_dir: ::core::option::Option<DefaultDir>,
+        }
+
+        impl DirectoryDaoBuilder {
+            #[doc = "Sets the `Directory` column."]
+            pub fn directory(mut self, directory: impl ::core::convert::Into<DirectoryIdentifier>) -> Self {
+                self.directory = Some(directory.into());
+                self
+            }
+
+            #[doc = "Sets the `Directory_Parent` column."]
+            pub fn parent_directory(
+                mut self,
+                parent_directory: impl ::core::convert::Into<DirectoryIdentifier>
+            ) -> Self {
+                self.parent_directory = Some(parent_directory.into());
+                self
+            }
+
+            #[doc = "Sets the `DefaultDir` column."]
+            pub fn default_dir(mut self, default_dir: impl ::core::convert::Into<DefaultDir>) -> Self {
+                self.default_dir = Some(default_dir.into());
+                self
+            }
+
+            #[doc = r" Creates the row. Fails if a column that is not nullable was never set or a value"]
+            #[doc = r" does not fit in its column."]
+            pub fn build(self) ->::core::result::Result<DirectoryDao, DirectoryError> {
+                let dao = DirectoryDao {
+                    directory: self.directory.ok_or(DirectoryError::MissingField { field: "directory", })?,
+                    parent_directory: self.parent_directory,
+                    default_dir: self.default_dir.ok_or(DirectoryError::MissingField { field: "default_dir", })?,
+                };
+                dao.check_constraints()?;
+                Ok(dao)
+            }
+        }
+
+        impl DirectoryDao {
+            #[doc = r" Starts building a row with every nullable column set to `None`."]
+            pub fn builder() -> DirectoryDaoBuilder { DirectoryDaoBuilder::default() }
+        }
+
+        #[derive(Clone, Debug, Default)]
         pub struct DirectoryTable {
             generator: DirectoryIdentifierGenerator,
-            entries: Vec<DirectoryDao>,
+            key_index: ::core::option::Option<::std::collections::HashMap<DirectoryKey, usize>>,
+            entries: ::std::vec::Vec<DirectoryDao>,
         }
 
-        impl MsiTable for DirectoryTable {
-            type TableValue = DirectoryDao;
+        impl ::core::cmp::PartialEq for DirectoryTable {
+            fn eq(&self, other: &Self) -> bool {
+                self.entries == other.entries && self.generator == other.generator
+            }
+        }
 
-            fn name(&self) -> &'static str {
-                "Directory"
+        impl DirectoryTable {
+            #[doc = "Creates an empty `DirectoryTable` whose generator never creates an identifier that is already in `used`. The identifiers of rows added to the table are recorded in `used` as well. Share `used` between tables so identifiers are unique across them."]
+            pub fn new(used: ::std::rc::Rc<::std::cell::RefCell<::std::vec::Vec<::whimsi_lib::types::column::identifier::Identifier>>>) -> DirectoryTable {
+                DirectoryTable {
+                    generator: used.into(),
+                    key_index: None,
+                    entries: ::std::vec::Vec::new(),
+                }
             }
 
-            fn entries(&self) -> &Vec<DirectoryDao> {
-                &self.entries
+            #[doc = r" The generator used to create identifiers for new rows in this table."]
+            pub fn generator_mut(&mut self) -> &mut DirectoryIdentifierGenerator { &mut self.generator }
+        }
+
+        impl DirectoryTable {
+            #[doc = r" The entry that `dao` cannot be stored next to because they share a primary key or a"]
+            #[doc = r" unique value. The primary key is looked up through the key index, `unique` columns"]
+            #[doc = r" and groups are compared with every entry."]
+            pub fn find_conflict(&self, dao: &DirectoryDao) ->::core::option::Option< &DirectoryDao> {
+                if let Some(position) = self.position_by_key(&dao.key()) {
+                    return Some(&self.entries[position]);
+                }
+                None
             }
 
-            fn entries_mut(&mut self) -> &mut Vec<DirectoryDao> {
-                &mut self.entries
+            #[doc = r" Adds the row for callers that know it does not conflict with the table."]
+            #[doc = r""]
+            #[doc = r" # Panics"]
+            #[doc = r""]
+            #[doc = r" Panics if the row is refused. Use `try_insert` to handle this instead."]
+            pub fn insert(&mut self, dao: DirectoryDao) {
+                self.try_insert(dao).unwrap_or_else(|error| panic!("{error}"))
+            }
+
+            #[doc = r" Adds the row unless it conflicts with a row that is already in the table."]
+            pub fn try_insert(&mut self, dao: DirectoryDao) ->::core::result::Result<(), DirectoryError> {
+                if let Some(entry) = self.find_conflict(&dao) {
+                    return Err(DirectoryError::Conflict { columns: entry.conflicting_columns(&dao), });
+                }
+                self.claim_identifier(&dao);
+                let position = self.entries.len();
+                self.key_index_mut().insert(dao.key(), position);
+                self.entries.push(dao);
+                Ok(())
+            }
+
+            #[doc = r" Removes and returns the entry at `index`."]
+            #[doc = r""]
+            #[doc = r" # Panics"]
+            #[doc = r""]
+            #[doc = r" Panics if `index` is out of bounds."]
+            pub fn remove(&mut self, index: usize) ->::core::result::Result<DirectoryDao, DirectoryError> {
+                let dao = self.entries.remove(index);
+                if let Some(key_index) = &mut self.key_index {
+                    key_index.remove(&dao.key());
+                    for position in key_index.values_mut() {
+                        if *position > index {
+                            *position -= 1;
+                        }
+                    }
+                }
+                self.release_identifier(&dao);
+                Ok(dao)
+            }
+
+            #[doc = r" The position of the entry with the given primary key."]
+            fn position_by_key(&self, key: &DirectoryKey) ->::core::option::Option<usize> {
+                match &self.key_index {
+                    Some(key_index) => key_index.get(key).copied(),
+                    None => self.entries.iter().position(|entry| entry.key() == *key),
+                }
+            }
+
+            #[doc = r" The position of every entry by its primary key. Rebuilt from the entries if it was"]
+            #[doc = r" dropped."]
+            fn key_index_mut(&mut self) -> &mut ::std::collections::HashMap<DirectoryKey, usize> {
+                let entries = &self.entries;
+                self.key_index.get_or_insert_with(| | {
+                    entries.iter().enumerate().map(|(position, entry)| (entry.key(), position)).collect()
+                })
+            }
+
+            #[doc = r" Adds the row or replaces the entry with the same primary key. Returns the replaced"]
+            #[doc = r" entry. Fails if the row conflicts with any other entry. Like `find_conflict` this"]
+            #[doc = r" compares the `unique` values of the row with every entry."]
+            pub fn upsert(&mut self, dao: DirectoryDao) ->::core::result::Result<::core::option::Option<DirectoryDao>, DirectoryError> {
+                let key = dao.key();
+                let index = self.position_by_key(&key);
+                Ok(match index {
+                    Some(index) => Some(::std::mem::replace(&mut self.entries[index], dao)),
+                    None => {
+                        self.claim_identifier(&dao);
+                        let position = self.entries.len();
+                        self.key_index_mut().insert(key, position);
+                        self.entries.push(dao);
+                        None
+                    }
+                })
+            }
+
+            #[doc = r" The entry with the given primary key."]
+            pub fn get(&self, key: &DirectoryKey) ->::core::option::Option< &DirectoryDao> {
+                self.position_by_key(key).map(|position| &self.entries[position])
+            }
+
+            #[doc = r" The entry with the given primary key. The primary key of the entry must not be changed"]
+            #[doc = r" through the reference as the table would no longer find it by its key."]
+            pub fn get_mut(&mut self, key: &DirectoryKey) ->::core::option::Option< &mut DirectoryDao> {
+                self.position_by_key(key).map(|position| &mut self.entries[position])
+            }
+
+            #[doc = r" Whether an entry has the given primary key."]
+            pub fn contains_key(&self, key: &DirectoryKey) -> bool { self.position_by_key(key).is_some() }
+
+            #[doc = r" The primary key of every entry in the order of the entries."]
+            pub fn primary_keys(&self) -> impl ::core::iter::Iterator<Item = DirectoryKey> + '_ { self.entries.iter().map(DirectoryDao::key) }
+
+            #[doc = r" Removes and returns the entry with the given primary key, if there is one."]
+            pub fn remove_by_key(&mut self, key: &DirectoryKey,) ->::core::result::Result<::core::option::Option<DirectoryDao>, DirectoryError> {
+                match self.position_by_key(key) {
+                    Some(index) => self.remove(index).map(Some),
+                    None => Ok(None),
+                }
+            }
+
+            #[doc = "The entries whose `Directory_Parent` column holds `value`."]
+            pub fn find_by_parent_directory<'a> (&'a self, value: &'a DirectoryIdentifier,) -> impl ::core::iter::Iterator<Item = &'a DirectoryDao> + 'a {
+                self.entries.iter().filter(move |entry| entry.parent_directory.as_ref() == Some(value))
+            }
+
+            #[doc = r" Records the identifier of `dao` as used so the generator does not create it again."]
+            fn claim_identifier(&self, dao: &DirectoryDao) {
+                let identifier = ::whimsi_lib::types::column::identifier::ToIdentifier::to_identifier(&dao.directory);
+                let mut used = ::whimsi_lib::types::helpers::id_generator::IdentifierGenerator::used(&self.generator).borrow_mut();
+                if !used.contains(&identifier) {
+                    used.push(identifier);
+                }
             }
 
-            fn primary_key_indices(&self) -> Vec<usize> {
-                vec![0usize,]
+            #[doc = r" Frees the identifier of `dao` so the generator may create it again."]
+            fn release_identifier(&self, dao: &DirectoryDao) {
+                let identifier = ::whimsi_lib::types::column::identifier::ToIdentifier::to_identifier(&dao.directory);
+                ::whimsi_lib::types::helpers::id_generator::IdentifierGenerator::used(&self.generator).borrow_mut().retain(|used| *used != identifier);
             }
+        }
 
-            fn primary_keys(&self) -> Vec<msi::ColumnType> {
-                vec![self.directory.into(),]
+        impl <K: ::core::convert::Into<DirectoryKey>>::std::ops::Index<K> for DirectoryTable {
+            type Output = DirectoryDao;
+            #[doc = r" The entry with the given primary key."]
+            #[doc = r""]
+            #[doc = r" # Panics"]
+            #[doc = r""]
+            #[doc = r" Panics if no entry has the key. Use `get` to handle this instead."]
+            fn index(&self, key: K) -> &DirectoryDao {
+                let key = key.into();
+                self.get(&key).unwrap_or_else(| |panic!("No entry with key {key:?}"))
             }
+        }
 
-            fn columns(&self) -> Vec<msi::Column> {
-                vec![
-                    msi::Column::build("Directory").primary_key().category(msi::Category::Identifier).string(72),
-                    msi::Column::build("Directory_Parent").nullable().foreign_key("Directory", 0).category(msi::Category::Identifier).string(72),
-                    msi::Column::build("DefaultDir").localizable().category(msi::Category::DefaultDir).string(255),
+        impl ::whimsi_lib::tables::MsiTableKind for DirectoryTable {
+            type TableValue = DirectoryDao;
+            fn name(&self) -> &'static str { "Directory" }
+            fn entries(&self) -> & ::std::vec::Vec<DirectoryDao> {
+                &self.entries
+            }
+            fn entries_mut(&mut self) -> &mut ::std::vec::Vec<DirectoryDao> {
+                self.key_index = None;
+                &mut self.entries
+            }
+            fn primary_key_indices(&self) ->::std::vec::Vec<usize> {
+                ::std::vec![0usize,]
+            }
+            fn columns(&self) ->::std::vec::Vec<::msi::Column> {
+                ::std::vec![
+                    ::msi::Column::build("Directory").primary_key().category(msi::Category::Identifier).string(72),
+                    ::msi::Column::build("Directory_Parent").nullable().foreign_key("Directory", 0).category(msi::Category::Identifier).string(72),
+                    ::msi::Column::build("DefaultDir").localizable().category(msi::Category::DefaultDir).string(255),
                 ]
             }
         }
 
+        impl DirectoryTable {
+            #[doc = "The rows of the `_Validation` table that describe the columns of the `Directory` table."]
+            pub fn validation_rows() ->::std::vec::Vec<::std::vec::Vec<::msi::Value>> {
+                ::std::vec![
+                    ::std::vec![
+                        ::msi::Value::Str(::std::string::String::from("Directory")),
+                        ::msi::Value::Str(::std::string::String::from("Directory")),
+                        ::msi::Value::Str(::std::string::String::from("N")),
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                        ::msi::Value::Str(::std::string::String::from("Identifier")),
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                    ],
+                    ::std::vec![
+                        ::msi::Value::Str(::std::string::String::from("Directory")),
+                        ::msi::Value::Str(::std::string::String::from("Directory_Parent")),
+                        ::msi::Value::Str(::std::string::String::from("Y")),
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                        ::msi::Value::Str(::std::string::String::from("Directory")),
+                        ::msi::Value::Int(1i32),
+                        ::msi::Value::Str(::std::string::String::from("Identifier")),
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                    ],
+                    ::std::vec![
+                        ::msi::Value::Str(::std::string::String::from("Directory")),
+                        ::msi::Value::Str(::std::string::String::from("DefaultDir")),
+                        ::msi::Value::Str(::std::string::String::from("N")),
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                        ::msi::Value::Str(::std::string::String::from("DefaultDir")),
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                    ],
+                ]
+            }
+        }
     };
 
     // Compare the generated output with the expected output (e.g., using syn and comparing ASTs)
@@ -174,70 +696,501 @@ fn test_msi_table_without_generated_identifier() {
     let output = msi_tables::gen_tables_impl(input);
 
     let expected_output = quote! {
-        use whimsi_lib::types::column::identifier::Identifier;
-        use whimsi_lib::types::column::identifier::ToIdentifier;
-        use whimsi_lib::types::helpers::id_generator::IdentifierGenerator;
+        #[doc = "Errors produced while creating a `FeatureComponentDao` or converting rows of the `FeatureComponent` table into one."]
+        #[derive(Clone, Debug, PartialEq)]
+        pub enum FeatureComponentError {
+            #[doc = r" The row does not contain the column."]
+            MissingColumn { column: &'static str },
+            #[doc = r" A builder was finished without setting a field that is not nullable."]
+            MissingField { field: &'static str },
+            #[doc = r" The value stored in the column could not be converted into the type of the field."]
+            InvalidValue {
+                column: &'static str,
+                value: ::std::string::String,
+                reason: ::std::string::String,
+            },
+            #[doc = r" The value breaks the `min`, `max` or `set` constraint of the column."]
+            NotAllowed {
+                column: &'static str,
+                value: ::std::string::String,
+                reason: ::std::string::String,
+            },
+            #[doc = r" The value of the field is longer than the `length` of its column."]
+            TooLong {
+                field: &'static str,
+                column: &'static str,
+                length: usize,
+                max_length: usize,
+            },
+            #[doc = r" The row has the same values as a row already in the table in columns that must be"]
+            #[doc = r" unique."]
+            Conflict { columns: ::std::vec::Vec< &'static str> },
+            #[doc = r" The value of a `unique_scope` column is already used by a row of a table that shares"]
+            #[doc = r" the registry."]
+            ScopeConflict {
+                column: &'static str,
+                scope: &'static str,
+                value: ::std::string::String,
+                table: &'static str,
+            },
+            #[doc = r" An `on_insert` or `on_remove` function of the table refused the change."]
+            Rejected { reason: ::std::string::String },
+        }
 
+        impl ::std::fmt::Display for FeatureComponentError {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) ->::std::fmt::Result {
+                match self {
+                    Self::MissingColumn { column } => {
+                        write!(f, "Table `FeatureComponent` has no column `{column}`", column = column)
+                    }
+                    Self::MissingField { field } => {
+                        write!(f, "Field `{field}` of `FeatureComponentDao` was never set", field = field)
+                    }
+                    Self::InvalidValue { column, value, reason } => write!(
+                        f,
+                        "Failed to convert value `{value}` in column `{column}` of table `FeatureComponent`: {reason}",
+                        column = column,
+                        value = value,
+                        reason = reason
+                    ),
+                    Self::NotAllowed { column, value, reason } => write!(
+                        f,
+                        "Value `{value}` is not allowed in column `{column}` of table `FeatureComponent`: {reason}",
+                        column = column,
+                        value = value,
+                        reason = reason
+                    ),
+                    Self::TooLong { field, column, length, max_length } => write!(
+                        f,
+                        "Value of field `{field}` is {length} characters long but column `{column}` of table `FeatureComponent` allows at most {max_length}",
+                        field = field,
+                        column = column,
+                        length = length,
+                        max_length = max_length
+                    ),
+                    Self::Conflict { columns } => {
+                        write!(
+                            f,
+                            "Row conflicts with an existing row of table `FeatureComponent` in columns {columns}",
+                            columns = columns.join(", ")
+                        )
+                    }
+                    Self::ScopeConflict { column, scope, value, table } => write!(
+                        f,
+                        "Value `{value}` of column `{column}` in table `FeatureComponent` is already used in scope `{scope}` by table `{table}`",
+                        column = column,
+                        scope = scope,
+                        value = value,
+                        table = table
+                    ),
+                    Self::Rejected { reason } => write!(
+                        f,
+                        "Row was rejected by table `FeatureComponent`: {reason}",
+                        reason = reason
+                    ),
+                }
+            }
+        }
+
+        impl ::std::error::Error for FeatureComponentError {}
+
+        #[derive(Clone, Debug, PartialEq)]
         pub struct FeatureComponentDao {
             feature_: FeatureIdentifier,
             component_: ComponentIdentifier,
         }
 
-        impl PrimaryIdentifier for FeatureComponentDao {
-            fn primary_identifier(&self) -> Option<Identifier> {
+        impl FeatureComponentDao {
+            pub fn feature_(&self) -> &FeatureIdentifier { &self.feature_ }
+            pub fn component_(&self) -> &ComponentIdentifier { &self.component_ }
+        }
+
+        impl FeatureComponentDao {
+            #[doc = r" Creates the row for callers whose values are already known to be valid. The values"]
+            #[doc = r" are not checked against their columns, use `try_new` for that."]
+            pub fn new(
+                feature_: impl ::core::convert::Into<FeatureIdentifier>,
+                component_: impl ::core::convert::Into<ComponentIdentifier>
+            ) -> FeatureComponentDao {
+                FeatureComponentDao {
+                    feature_: feature_.into(),
+                    component_: component_.into(),
+                }
+            }
+
+            #[doc = r" Creates the row after checking that every value fits in its column. See"]
+            #[doc = r" `check_constraints`."]
+            pub fn try_new(
+                feature_: impl ::core::convert::Into<FeatureIdentifier>,
+                component_: impl ::core::convert::Into<ComponentIdentifier>
+            ) ->::core::result::Result<FeatureComponentDao, FeatureComponentError> {
+                let dao = Self::new(feature_, component_);
+                dao.check_constraints()?;
+                Ok(dao)
+            }
+        }
+
+        impl FeatureComponentDao {
+            #[doc = r" Checks the value of every field against the `length`, `min`, `max` and `set` of its"]
+            #[doc = r" column."]
+            pub fn check_constraints(&self) ->::core::result::Result<(), FeatureComponentError> {
+                {
+                    let value = ::msi::ToValue::to_value(&self.feature_);
+                    if let ::msi::Value::Str(text) = &value {
+                        let max_length = (72) as usize;
+                        let length = text.chars().count();
+                        if max_length != 0 && length > max_length {
+                            return Err(FeatureComponentError::TooLong {
+                                field: "feature_",
+                                column: "Feature_",
+                                length,
+                                max_length,
+                            });
+                        }
+                    }
+                }
+                {
+                    let value = ::msi::ToValue::to_value(&self.component_);
+                    if let ::msi::Value::Str(text) = &value {
+                        let max_length = (72) as usize;
+                        let length = text.chars().count();
+                        if max_length != 0 && length > max_length {
+                            return Err(FeatureComponentError::TooLong {
+                                field: "component_",
+                                column: "Component_",
+                                length,
+                                max_length,
+                            });
+                        }
+                    }
+                }
+                Ok(())
+            }
+        }
+
+        impl FeatureComponentDao {
+            #[doc = "Reads a `FeatureComponentDao` from a row of the `FeatureComponent` table. Columns are looked up by name so the order of the columns in the row does not matter."]
+            pub fn from_row(row: & ::msi::Row) ->::core::result::Result<FeatureComponentDao, FeatureComponentError> {
+                let dao = FeatureComponentDao {
+                    feature_: {
+                        if !row.has_column("Feature_") {
+                            return Err(FeatureComponentError::MissingColumn { column: "Feature_" });
+                        }
+                        let value = &row["Feature_"];
+                        match <FeatureIdentifier as ::core::convert::TryFrom<::msi::Value>>::try_from(value.clone()) {
+                            Ok(converted) => converted,
+                            Err(error) => {
+                                return Err(FeatureComponentError::InvalidValue {
+                                    column: "Feature_",
+                                    value: value.to_string(),
+                                    reason: ::std::string::ToString::to_string(&error),
+                                });
+                            }
+                        }
+                    },
+                    component_: {
+                        if !row.has_column("Component_") {
+                            return Err(FeatureComponentError::MissingColumn { column: "Component_" });
+                        }
+                        let value = &row["Component_"];
+                        match <ComponentIdentifier as ::core::convert::TryFrom<::msi::Value>>::try_from(value.clone()) {
+                            Ok(converted) => converted,
+                            Err(error) => {
+                                return Err(FeatureComponentError::InvalidValue {
+                                    column: "Component_",
+                                    value: value.to_string(),
+                                    reason: ::std::string::ToString::to_string(&error),
+                                });
+                            }
+                        }
+                    },
+                };
+                dao.check_constraints()?;
+                Ok(dao)
+            }
+        }
+
+        impl ::core::convert::TryFrom<::msi::Row> for FeatureComponentDao {
+            type Error = FeatureComponentError;
+            fn try_from(row: ::msi::Row) ->::core::result::Result<Self, Self::Error> {
+                Self::from_row(&row)
+            }
+        }
+
+        impl ::whimsi_lib::tables::PrimaryIdentifier for FeatureComponentDao {
+            fn primary_identifier(&self) ->::core::option::Option<::whimsi_lib::types::column::identifier::Identifier> {
                 None
             }
         }
 
-        impl MsiDao for FeatureComponentDao {
+        impl FeatureComponentDao {
+            #[doc = r" The columns that hold the same values in both rows where the table requires them to"]
+            #[doc = r" be unique. Empty when both rows can be stored in the same table."]
+            pub fn conflicting_columns(&self, other: &Self) ->::std::vec::Vec< &'static str> {
+                let mut columns = ::std::vec::Vec::new();
+                if self.feature_ == other.feature_ && self.component_ == other.component_ {
+                    columns.extend(["Feature_", "Component_"]);
+                }
+                columns
+            }
+        }
+
+        #[doc = "The primary key of a row of the `FeatureComponent` table. Holds the `primary_key` fields in the order they are declared."]
+        #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+        pub struct FeatureComponentKey(pub FeatureIdentifier, pub ComponentIdentifier);
 
-            fn conflicts_with(&self, other: &Self) -> bool {
-                self.feature_ == other.feature_ && self.component_ == other.component_
+        impl ::core::convert::From<(FeatureIdentifier, ComponentIdentifier)> for FeatureComponentKey {
+            fn from(value: (FeatureIdentifier, ComponentIdentifier)) -> Self { FeatureComponentKey(value.0, value.1) }
+        }
+
+        impl FeatureComponentDao {
+            #[doc = r" The primary key of the row."]
+            pub fn key(&self) -> FeatureComponentKey {
+                FeatureComponentKey(self.feature_.clone(), self.component_.clone())
             }
+        }
 
-            fn to_row(&self) -> Vec<msi::Value> {
-                vec![
-                    msi::ToValue::to_value(self.feature_),
-                    msi::ToValue::to_value(self.component_),
+        impl ::whimsi_lib::tables::MsiDao for FeatureComponentDao {
+            fn conflicts_with(&self, other: &Self) -> bool { !self.conflicting_columns(other).is_empty() }
+            fn to_row(&self) ->::std::vec::Vec<::msi::Value> {
+                ::std::vec![
+                    ::msi::ToValue::to_value(&self.feature_),
+                    ::msi::ToValue::to_value(&self.component_),
                 ]
             }
         }
 
+        #[doc = "Builds a `FeatureComponentDao` one named column at a time. Create one with `FeatureComponentDao::builder`."]
+        #[derive(Clone, Debug, Default)]
+        pub struct FeatureComponentDaoBuilder {
+            feature_: ::core::option::Option<FeatureIdentifier>,
+            component_: ::core::option::Option<ComponentIdentifier>,
+        }
+
+        impl FeatureComponentDaoBuilder {
+            #[doc = "Sets the `Feature_` column."]
+            pub fn feature_(mut self, feature_: impl ::core::convert::Into<FeatureIdentifier>) -> Self {
+                self.feature_ = Some(feature_.into());
+                self
+            }
+
+            #[doc = "Sets the `Component_` column."]
+            pub fn component_(mut self, component_: impl ::core::convert::Into<ComponentIdentifier>) -> Self {
+                self.component_ = Some(component_.into());
+                self
+            }
+
+            #[doc = r" Creates the row. Fails if a column that is not nullable was never set or a value"]
+            #[doc = r" does not fit in its column."]
+            pub fn build(self) ->::core::result::Result<FeatureComponentDao, FeatureComponentError> {
+                let dao = FeatureComponentDao {
+                    feature_: self.feature_.ok_or(FeatureComponentError::MissingField { field: "feature_", })?,
+                    component_: self.component_.ok_or(FeatureComponentError::MissingField { field: "component_", })?,
+                };
+                dao.check_constraints()?;
+                Ok(dao)
+            }
+        }
+
+        impl FeatureComponentDao {
+            #[doc = r" Starts building a row with every nullable column set to `None`."]
+            pub fn builder() -> FeatureComponentDaoBuilder { FeatureComponentDaoBuilder::default() }
+        }
+
+        #[derive(Clone, Debug, Default)]
         pub struct FeatureComponentTable {
-            entries: Vec<FeatureComponentDao>,
+            key_index: ::core::option::Option<::std::collections::HashMap<FeatureComponentKey, usize>>,
+            entries: ::std::vec::Vec<FeatureComponentDao>,
         }
 
-        impl MsiTable for FeatureComponentTable {
-            type TableValue = FeatureComponentDao;
+        impl ::core::cmp::PartialEq for FeatureComponentTable {
+            fn eq(&self, other: &Self) -> bool { self.entries == other.entries }
+        }
 
-            fn name(&self) -> &'static str {
-                "FeatureComponent"
+        impl FeatureComponentTable {
+            #[doc = r" The entry that `dao` cannot be stored next to because they share a primary key or a"]
+            #[doc = r" unique value. The primary key is looked up through the key index, `unique` columns"]
+            #[doc = r" and groups are compared with every entry."]
+            pub fn find_conflict(&self, dao: &FeatureComponentDao) ->::core::option::Option< &FeatureComponentDao> {
+                if let Some(position) = self.position_by_key(&dao.key()) {
+                    return Some(&self.entries[position]);
+                }
+                None
             }
 
-            fn entries(&self) -> &Vec<FeatureComponentDao> {
-                &self.entries
+            #[doc = r" Adds the row for callers that know it does not conflict with the table."]
+            #[doc = r""]
+            #[doc = r" # Panics"]
+            #[doc = r""]
+            #[doc = r" Panics if the row is refused. Use `try_insert` to handle this instead."]
+            pub fn insert(&mut self, dao: FeatureComponentDao) {
+                self.try_insert(dao).unwrap_or_else(|error| panic!("{error}"))
             }
 
-            fn entries_mut(&mut self) -> &mut Vec<FeatureComponentDao> {
-                &mut self.entries
+            #[doc = r" Adds the row unless it conflicts with a row that is already in the table."]
+            pub fn try_insert(&mut self, dao: FeatureComponentDao) ->::core::result::Result<(), FeatureComponentError> {
+                if let Some(entry) = self.find_conflict(&dao) {
+                    return Err(FeatureComponentError::Conflict { columns: entry.conflicting_columns(&dao), });
+                }
+                let position = self.entries.len();
+                self.key_index_mut().insert(dao.key(), position);
+                self.entries.push(dao);
+                Ok(())
+            }
+
+            #[doc = r" Removes and returns the entry at `index`."]
+            #[doc = r""]
+            #[doc = r" # Panics"]
+            #[doc = r""]
+            #[doc = r" Panics if `index` is out of bounds."]
+            pub fn remove(&mut self, index: usize) ->::core::result::Result<FeatureComponentDao, FeatureComponentError> {
+                let dao = self.entries.remove(index);
+                if let Some(key_index) = &mut self.key_index {
+                    key_index.remove(&dao.key());
+                    for position in key_index.values_mut() {
+                        if *position > index {
+                            *position -= 1;
+                        }
+                    }
+                }
+                Ok(dao)
+            }
+
+            #[doc = r" The position of the entry with the given primary key."]
+            fn position_by_key(&self, key: &FeatureComponentKey) ->::core::option::Option<usize> {
+                match &self.key_index {
+                    Some(key_index) => key_index.get(key).copied(),
+                    None => self.entries.iter().position(|entry| entry.key() == *key),
+                }
+            }
+
+            #[doc = r" The position of every entry by its primary key. Rebuilt from the entries if it was"]
+            #[doc = r" dropped."]
+            fn key_index_mut(&mut self) -> &mut ::std::collections::HashMap<FeatureComponentKey, usize> {
+                let entries = &self.entries;
+                self.key_index.get_or_insert_with(| | {
+                    entries.iter().enumerate().map(|(position, entry)| (entry.key(), position)).collect()
+                })
+            }
+
+            #[doc = r" Adds the row or replaces the entry with the same primary key. Returns the replaced"]
+            #[doc = r" entry. Fails if the row conflicts with any other entry. Like `find_conflict` this"]
+            #[doc = r" compares the `unique` values of the row with every entry."]
+            pub fn upsert(&mut self, dao: FeatureComponentDao) ->::core::result::Result<::core::option::Option<FeatureComponentDao>, FeatureComponentError> {
+                let key = dao.key();
+                let index = self.position_by_key(&key);
+                Ok(match index {
+                    Some(index) => Some(::std::mem::replace(&mut self.entries[index], dao)),
+                    None => {
+                        let position = self.entries.len();
+                        self.key_index_mut().insert(key, position);
+                        self.entries.push(dao);
+                        None
+                    }
+                })
+            }
+
+            #[doc = r" The entry with the given primary key."]
+            pub fn get(&self, key: &FeatureComponentKey) ->::core::option::Option< &FeatureComponentDao> {
+                self.position_by_key(key).map(|position| &self.entries[position])
+            }
+
+            #[doc = r" The entry with the given primary key. The primary key of the entry must not be changed"]
+            #[doc = r" through the reference as the table would no longer find it by its key."]
+            pub fn get_mut(&mut self, key: &FeatureComponentKey) ->::core::option::Option< &mut FeatureComponentDao> {
+                self.position_by_key(key).map(|position| &mut self.entries[position])
+            }
+
+            #[doc = r" Whether an entry has the given primary key."]
+            pub fn contains_key(&self, key: &FeatureComponentKey) -> bool { self.position_by_key(key).is_some() }
+
+            #[doc = r" The primary key of every entry in the order of the entries."]
+            pub fn primary_keys(&self) -> impl ::core::iter::Iterator<Item = FeatureComponentKey> + '_ { self.entries.iter().map(FeatureComponentDao::key) }
+
+            #[doc = r" Removes and returns the entry with the given primary key, if there is one."]
+            pub fn remove_by_key(&mut self, key: &FeatureComponentKey,) ->::core::result::Result<::core::option::Option<FeatureComponentDao>, FeatureComponentError> {
+                match self.position_by_key(key) {
+                    Some(index) => self.remove(index).map(Some),
+                    None => Ok(None),
+                }
             }
 
-            fn primary_key_indices(&self) -> Vec<usize> {
-                vec![0usize,1usize,]
+            #[doc = "The entries whose `Feature_` column holds `value`."]
+            pub fn find_by_feature_<'a> (&'a self, value: &'a FeatureIdentifier,) -> impl ::core::iter::Iterator<Item = &'a FeatureComponentDao> + 'a {
+                self.entries.iter().filter(move |entry| Some(&entry.feature_) == Some(value))
             }
 
-            fn primary_keys(&self) -> Vec<msi::ColumnType> {
-                vec![self.feature_.into(), self.component_.into(),]
+            #[doc = "The entries whose `Component_` column holds `value`."]
+            pub fn find_by_component_<'a> (&'a self, value: &'a ComponentIdentifier,) -> impl ::core::iter::Iterator<Item = &'a FeatureComponentDao> + 'a {
+                self.entries.iter().filter(move |entry| Some(&entry.component_) == Some(value))
             }
+        }
+
+        impl <K: ::core::convert::Into<FeatureComponentKey>>::std::ops::Index<K> for FeatureComponentTable {
+            type Output = FeatureComponentDao;
+            #[doc = r" The entry with the given primary key."]
+            #[doc = r""]
+            #[doc = r" # Panics"]
+            #[doc = r""]
+            #[doc = r" Panics if no entry has the key. Use `get` to handle this instead."]
+            fn index(&self, key: K) -> &FeatureComponentDao {
+                let key = key.into();
+                self.get(&key).unwrap_or_else(| |panic!("No entry with key {key:?}"))
+            }
+        }
 
-            fn columns(&self) -> Vec<msi::Column> {
-                vec![
-                    msi::Column::build("Feature_").primary_key().foreign_key("Feature", 0).category(msi::Category::Identifier).string(72),
-                    msi::Column::build("Component_").primary_key().foreign_key("Component", 0).category(msi::Category::Identifier).string(72),
+        impl ::whimsi_lib::tables::MsiTableKind for FeatureComponentTable {
+            type TableValue = FeatureComponentDao;
+            fn name(&self) -> &'static str { "FeatureComponent" }
+            fn entries(&self) -> & ::std::vec::Vec<FeatureComponentDao> {
+                &self.entries
+            }
+            fn entries_mut(&mut self) -> &mut ::std::vec::Vec<FeatureComponentDao> {
+                self.key_index = None;
+                &mut self.entries
+            }
+            fn primary_key_indices(&self) ->::std::vec::Vec<usize> {
+                ::std::vec![0usize, 1usize,]
+            }
+            fn columns(&self) ->::std::vec::Vec<::msi::Column> {
+                ::std::vec![
+                    ::msi::Column::build("Feature_").primary_key().foreign_key("Feature", 0).category(msi::Category::Identifier).string(72),
+                    ::msi::Column::build("Component_").primary_key().foreign_key("Component", 0).category(msi::Category::Identifier).string(72),
                 ]
             }
         }
 
+        impl FeatureComponentTable {
+            #[doc = "The rows of the `_Validation` table that describe the columns of the `FeatureComponent` table."]
+            pub fn validation_rows() ->::std::vec::Vec<::std::vec::Vec<::msi::Value>> {
+                ::std::vec![
+                    ::std::vec![
+                        ::msi::Value::Str(::std::string::String::from("FeatureComponent")),
+                        ::msi::Value::Str(::std::string::String::from("Feature_")),
+                        ::msi::Value::Str(::std::string::String::from("N")),
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                        ::msi::Value::Str(::std::string::String::from("Feature")),
+                        ::msi::Value::Int(1i32),
+                        ::msi::Value::Str(::std::string::String::from("Identifier")),
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                    ],
+                    ::std::vec![
+                        ::msi::Value::Str(::std::string::String::from("FeatureComponent")),
+                        ::msi::Value::Str(::std::string::String::from("Component_")),
+                        ::msi::Value::Str(::std::string::String::from("N")),
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                        ::msi::Value::Str(::std::string::String::from("Component")),
+                        ::msi::Value::Int(1i32),
+                        ::msi::Value::Str(::std::string::String::from("Identifier")),
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                    ],
+                ]
+            }
+        }
     };
 
     // Compare the generated output with the expected output (e.g., using syn and comparing ASTs)
@@ -265,11 +1218,13 @@ fn test_msi_tables_enum() {
                 default_dir: DefaultDir,
             },
 
-            FeatureComponent {
-                #[msi_column(primary_key, identifier(foreign_key = "Feature"), category = msi::Category::Identifier, length = 72)]
-                feature_: FeatureIdentifier,
-                #[msi_column(primary_key, identifier(foreign_key = "Component"), category = msi::Category::Identifier, length = 72)]
-                component_: ComponentIdentifier,
+            Component {
+                #[msi_column(primary_key, identifier(generated), category = msi::Category::Identifier, length = 72)]
+                component: ComponentIdentifier,
+                #[msi_column(identifier(foreign_key = "Directory"), category = msi::Category::Identifier, length = 72)]
+                directory_: DirectoryIdentifier,
+                #[msi_column(category = msi::Category::Integer)]
+                attributes: i16,
             }
         }
     };
@@ -278,200 +1233,2956 @@ fn test_msi_tables_enum() {
     let output = msi_tables::gen_tables_impl(input);
 
     let expected_output = quote! {
-        use whimsi_lib::types::column::identifier::Identifier;
-        use whimsi_lib::types::column::identifier::ToIdentifier;
-        use whimsi_lib::types::helpers::id_generator::IdentifierGenerator;
-
+        #[derive(Clone, PartialEq)]
         pub enum MsiTables {
             Directory(DirectoryTable),
-            FeatureComponent(FeatureComponentTable),
+            Component(ComponentTable),
         }
 
-        #[doc = "This is a simple wrapper around `Identifier` for the `DirectoryTable`. Used to ensure that identifiers for the `DirectoryTable` are only used in valid locations."]
-        pub struct DirectoryIdentifier(Identifier);
+        #[doc = r" The kind of table stored in each variant."]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum MsiTablesKind { Directory, Component, }
 
-        impl ToIdentifier for DirectoryIdentifier {
-            fn to_identifier(&self) -> Identifier {
-                self.0
+        impl ::core::convert::From< &MsiTables> for MsiTablesKind {
+            fn from(value: &MsiTables) -> Self {
+                match value {
+                    MsiTables::Directory(_) => MsiTablesKind::Directory,
+                    MsiTables::Component(_) => MsiTablesKind::Component,
+                }
             }
         }
-        impl std::str::FromStr for DirectoryIdentifier {
-            type Err = anyhow::Error;
 
-            fn from_str(s: &str) -> anyhow::Result<Self> {
-                Ok(Self(Identifier::from_str(s)?))
+        impl ::std::fmt::Display for MsiTables {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) ->::std::fmt::Result {
+                match self {
+                    MsiTables::Directory(_) => f.write_str("Directory"),
+                    MsiTables::Component(_) => f.write_str("Component"),
+                }
             }
         }
 
-        #[derive(Debug, Clone, Default, PartialEq)]
-        pub(crate) struct DirectoryIdentifierGenerator {
-            count: usize,
-            // A reference to a vec of all used Identifiers that should not be generated again.
-            // These are all identifiers that inhabit a primary_key column.
-            used: std::rc::Rc<std::cell::RefCell<Vec<Identifier>>>,
+        impl ::core::convert::From<DirectoryTable> for MsiTables {
+            fn from(value: DirectoryTable) -> Self { MsiTables::Directory(value) }
         }
 
-        impl IdentifierGenerator for DirectoryIdentifierGenerator {
-            type IdentifierType = DirectoryIdentifier;
+        impl ::core::convert::TryFrom<MsiTables> for DirectoryTable {
+            type Error = MsiTables;
+            fn try_from(value: MsiTables) ->::core::result::Result<Self, Self::Error> {
+                match value {
+                    MsiTables::Directory(table) => Ok(table),
+                    #[allow(unreachable_patterns)]
+                    other => Err(other),
+                }
+            }
+        }
+
+        impl ::core::convert::From<ComponentTable> for MsiTables {
+            fn from(value: ComponentTable) -> Self { MsiTables::Component(value) }
+        }
 
-            fn id_prefix(&self) -> &str {
-                "DIRECTORY"
+        impl ::core::convert::TryFrom<MsiTables> for ComponentTable {
+            type Error = MsiTables;
+            fn try_from(value: MsiTables) ->::core::result::Result<Self, Self::Error> {
+                match value {
+                    MsiTables::Component(table) => Ok(table),
+                    #[allow(unreachable_patterns)]
+                    other => Err(other),
+                }
             }
+        }
 
-            fn used(&self) -> &std::rc::Rc<std::cell::RefCell<Vec<Identifier>>> {
-                &self.used
+        impl MsiTables {
+            #[doc = r" The rows of the `_Validation` table that describe every table in this list."]
+            pub fn validation_rows() ->::std::vec::Vec<::std::vec::Vec<::msi::Value>> {
+                let mut rows = ::std::vec::Vec::new();
+                rows.extend(DirectoryTable::validation_rows());
+                rows.extend(ComponentTable::validation_rows());
+                rows
+            }
+        }
+
+        #[derive(Clone, Debug, PartialEq)]
+        pub enum MsiTablesDao { Directory(DirectoryDao), Component(ComponentDao), }
+
+        #[doc = r" The values of `unique_scope` columns used by the rows of every table that shares this"]
+        #[doc = r" registry. Share one registry between the tables with `with_registry` so a value can only"]
+        #[doc = r" be used once in each scope across all of them."]
+        #[derive(Clone, Debug, Default, PartialEq)]
+        pub struct MsiTablesRegistry {
+            values: ::std::collections::HashMap<(&'static str, ::msi::Value), &'static str>,
+        }
+
+        impl MsiTablesRegistry {
+            #[doc = r" The name of the table whose row already uses `value` in `scope`."]
+            pub fn owner(&self, scope: &'static str, value: & ::msi::Value) ->::core::option::Option< &'static str> {
+                self.values.get(&(scope, value.clone())).copied()
             }
 
-            fn count(&self) -> usize {
-                self.count
+            #[doc = r" Records that a row of `table` uses `value` in `scope`. Returns the table that already"]
+            #[doc = r" uses the value instead if there is one."]
+            pub fn register(&mut self, scope: &'static str, value: ::msi::Value, table: &'static str,) ->::core::result::Result<(), &'static str> {
+                match self.values.entry((scope, value)) {
+                    ::std::collections::hash_map::Entry::Occupied(entry) => Err(*entry.get()),
+                    ::std::collections::hash_map::Entry::Vacant(entry) => {
+                        entry.insert(table);
+                        Ok(())
+                    }
+                }
             }
 
-            fn count_mut(&mut self) -> &mut usize {
-                &mut self.count
+            #[doc = r" Frees `value` in `scope` so another row may use it."]
+            pub fn unregister(&mut self, scope: &'static str, value: & ::msi::Value) {
+                self.values.remove(&(scope, value.clone()));
             }
         }
 
-        impl From<std::rc::Rc<std::cell::RefCell<Vec<Identifier>>>> for DirectoryIdentifierGenerator {
-            fn from(value: std::rc::Rc<std::cell::RefCell<Vec<Identifier>>>) -> Self {
-                let count = value.borrow().len();
+        #[doc = "One of every table in `MsiTables`. Tables created with `new` share the identifiers used by their generators and the registry of their `unique_scope` columns."]
+        #[derive(Clone, Debug, PartialEq)]
+        pub struct MsiTablesContainer {
+            pub directory: DirectoryTable,
+            pub component: ComponentTable,
+        }
+
+        impl MsiTablesContainer {
+            #[doc = r" Creates every table empty."]
+            pub fn new() -> Self {
+                let used = ::std::rc::Rc::new(::std::cell::RefCell::new(::std::vec::Vec::new()));
                 Self {
-                    used: value,
-                    count: 0,
+                    directory: DirectoryTable::new(used.clone()),
+                    component: ComponentTable::new(used.clone()),
                 }
             }
         }
 
-        pub struct DirectoryDao {
-            directory: DirectoryIdentifier,
-            parent_directory: Option<DirectoryIdentifier>,
-            default_dir: DefaultDir,
+        impl ::core::default::Default for MsiTablesContainer {
+            fn default() -> Self { Self::new() }
         }
 
-        impl PrimaryIdentifier for DirectoryDao {
-            fn primary_identifier(&self) -> Option<Identifier> {
-                Some( self.directory.to_identifier() )
-            }
+        #[doc = r" A foreign key whose value is not in the table that it references."]
+        #[derive(Clone, Debug, PartialEq)]
+        pub struct MsiTablesDanglingReference {
+            #[doc = r" The table of the row holding the foreign key."]
+            pub table: MsiTablesKind,
+            #[doc = r" The primary key of the row, or every value of the row if its table has no primary"]
+            #[doc = r" key."]
+            pub key: ::std::vec::Vec<::msi::Value>,
+            #[doc = r" The column holding the foreign key."]
+            pub column: &'static str,
+            #[doc = r" The value that is missing from the referenced table."]
+            pub value: ::msi::Value,
+            #[doc = r" The table that the column references."]
+            pub referenced_table: MsiTablesKind,
         }
 
-        impl MsiDao for DirectoryDao {
-
-            fn conflicts_with(&self, other: &Self) -> bool {
-                self.directory == other.directory
+        impl ::std::fmt::Display for MsiTablesDanglingReference {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) ->::std::fmt::Result {
+                let key = self.key.iter().map(::std::string::ToString::to_string).collect::<::std::vec::Vec<_>> ();
+                write!(
+                    f,
+                    "Column `{column}` of row ({key}) in table `{table:?}` references `{value}` which is not in table `{referenced_table:?}`",
+                    column = self.column,
+                    key = key.join(", "),
+                    table = self.table,
+                    value = self.value,
+                    referenced_table = self.referenced_table
+                )
             }
+        }
 
-            fn to_row(&self) -> Vec<msi::Value> {
-                vec![
-                    msi::ToValue::to_value(self.directory),
-                    msi::ToValue::to_value(self.parent_directory),
-                    msi::ToValue::to_value(self.default_dir),
-                ]
+        impl MsiTablesContainer {
+            #[doc = r" Every foreign key of every table whose value is not in the table that it references."]
+            #[doc = r" Null values are never reported."]
+            pub fn check_references(&self) ->::std::vec::Vec<MsiTablesDanglingReference> {
+                let mut dangling = ::std::vec::Vec::new();
+                {
+                    let referenced = self.directory.entries.iter().filter_map(|entry| Some(&entry.directory)).map(::msi::ToValue::to_value).collect::<::std::collections::HashSet<_>> ();
+                    for entry in &self.directory.entries {
+                        let Some(value) = entry.parent_directory.as_ref() else {
+                            continue;
+                        };
+                        let value = ::msi::ToValue::to_value(value);
+                        if !referenced.contains(&value) {
+                            dangling.push(MsiTablesDanglingReference {
+                                table: MsiTablesKind::Directory,
+                                key: ::std::vec![::msi::ToValue::to_value(&entry.directory)],
+                                column: "Directory_Parent",
+                                value,
+                                referenced_table: MsiTablesKind::Directory,
+                            });
+                        }
+                    }
+                }
+                {
+                    let referenced = self.directory.entries.iter().filter_map(|entry| Some(&entry.directory)).map(::msi::ToValue::to_value).collect::<::std::collections::HashSet<_>> ();
+                    for entry in &self.component.entries {
+                        let Some(value) = Some(&entry.directory_) else {
+                            continue;
+                        };
+                        let value = ::msi::ToValue::to_value(value);
+                        if !referenced.contains(&value) {
+                            dangling.push(MsiTablesDanglingReference {
+                                table: MsiTablesKind::Component,
+                                key: ::std::vec![::msi::ToValue::to_value(&entry.component)],
+                                column: "Directory_",
+                                value,
+                                referenced_table: MsiTablesKind::Directory,
+                            });
+                        }
+                    }
+                }
+                dangling
             }
         }
 
-        pub struct DirectoryTable {
-            generator: DirectoryIdentifierGenerator,
-            entries: Vec<DirectoryDao>,
+        #[doc = r" How `remove_cascade` treats the rows that reference a removed row."]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub enum MsiTablesCascade {
+            #[doc = r" Removes every row that references a removed row."]
+            Delete,
+            #[doc = r" Sets nullable references to null and removes the rows holding any other"]
+            #[doc = r" reference."]
+            NullOut,
         }
 
-        impl MsiTable for DirectoryTable {
-            type TableValue = DirectoryDao;
-            fn name(&self) -> &'static str {
-                "Directory"
-            }
+        #[doc = r" A row that `remove_cascade` still has to remove."]
+        enum MsiTablesCascadeRow { Directory(DirectoryKey), Component(ComponentKey), }
 
-            fn entries(&self) -> &Vec<DirectoryDao> {
-                &self.entries
-            }
+        #[doc = "The error of the table that refused a change made through the `MsiTablesContainer`."]
+        #[derive(Clone, Debug, PartialEq)]
+        pub enum MsiTablesError {
+            Directory(DirectoryError),
+            Component(ComponentError),
+        }
 
-            fn entries_mut(&mut self) -> &mut Vec<DirectoryDao> {
-                &mut self.entries
-            }
-            fn primary_key_indices(&self) -> Vec<usize> {
-                vec![0usize,]
+        impl ::std::fmt::Display for MsiTablesError {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) ->::std::fmt::Result {
+                match self {
+                    Self::Directory(error) =>::std::fmt::Display::fmt(error, f),
+                    Self::Component(error) =>::std::fmt::Display::fmt(error, f),
+                }
             }
+        }
 
-            fn primary_keys(&self) -> Vec<msi::ColumnType> {
-                vec![self.directory.into(),]
-            }
+        impl ::std::error::Error for MsiTablesError {}
 
-            fn columns(&self) -> Vec<msi::Column> {
-                vec![
-                    msi::Column::build("Directory").primary_key().category(msi::Category::Identifier).string(72),
-                    msi::Column::build("Directory_Parent").nullable().foreign_key("Directory", 0).category(msi::Category::Identifier).string(72),
-                    msi::Column::build("DefaultDir").localizable().category(msi::Category::DefaultDir).string(255),
-                ]
+        impl MsiTablesContainer {
+            #[doc = r" Removes the row of `table` with the given primary key, or with the given values if"]
+            #[doc = r" the table has no primary key, along with every row that references it. Rows that"]
+            #[doc = r" reference a removed row are handled as `cascade` says. Returns every removed row."]
+            #[doc = r""]
+            #[doc = r" The row itself is found by comparing `key` with every entry of `table`. The rows"]
+            #[doc = r" that reference it are then found by their primary key."]
+            #[doc = r""]
+            #[doc = r" Stops at the first row that the `on_remove` of its table refuses. Rows removed"]
+            #[doc = r" before then stay removed."]
+            pub fn remove_cascade(
+                &mut self,
+                table: MsiTablesKind,
+                key: &[::msi::Value],
+                cascade: MsiTablesCascade,
+            ) ->::core::result::Result<::std::vec::Vec<MsiTablesDao>, MsiTablesError> {
+                let mut removed = ::std::vec::Vec::new();
+                let first = match table {
+                    MsiTablesKind::Directory => self.directory.entries.iter().find(|&entry| ::std::vec![::msi::ToValue::to_value(&entry.directory)] == key).map(|entry| MsiTablesCascadeRow::Directory(entry.key())),
+                    MsiTablesKind::Component => self.component.entries.iter().find(|&entry| ::std::vec![::msi::ToValue::to_value(&entry.component)] == key).map(|entry| MsiTablesCascadeRow::Component(entry.key())),
+                };
+                let mut pending = first.into_iter().collect::<::std::vec::Vec<_>> ();
+                while let Some(row) = pending.pop() {
+                    let dao = match row {
+                        MsiTablesCascadeRow::Directory(key) => {
+                            let Some(position) = self.directory.position_by_key(&key) else {
+                                continue;
+                            };
+                            let dao = self.directory.remove(position).map_err(MsiTablesError::Directory)?;
+                            MsiTablesDao::Directory(dao)
+                        }
+                        MsiTablesCascadeRow::Component(key) => {
+                            let Some(position) = self.component.position_by_key(&key) else {
+                                continue;
+                            };
+                            let dao = self.component.remove(position).map_err(MsiTablesError::Component)?;
+                            MsiTablesDao::Component(dao)
+                        }
+                    };
+                    match &dao {
+                        MsiTablesDao::Directory(dao) => {
+                            if let Some(value) = Some(&dao.directory) {
+                                let value = ::msi::ToValue::to_value(value);
+                                if cascade == MsiTablesCascade::NullOut {
+                                    let entries = ::whimsi_lib::tables::MsiTableKind::entries_mut(&mut self.directory);
+                                    for entry in entries.iter_mut() {
+                                        if entry.parent_directory.as_ref().map(::msi::ToValue::to_value).as_ref() == Some(&value) {
+                                            entry.parent_directory = None;
+                                        }
+                                    }
+                                } else {
+                                    for entry in &self.directory.entries {
+                                        if entry.parent_directory.as_ref().map(::msi::ToValue::to_value).as_ref() == Some(&value) {
+                                            pending.push(MsiTablesCascadeRow::Directory(entry.key()));
+                                        }
+                                    }
+                                }
+                            }
+                            if let Some(value) = Some(&dao.directory) {
+                                let value = ::msi::ToValue::to_value(value);
+                                for entry in &self.component.entries {
+                                    if Some(&entry.directory_).map(::msi::ToValue::to_value).as_ref() == Some(&value) {
+                                        pending.push(MsiTablesCascadeRow::Component(entry.key()));
+                                    }
+                                }
+                            }
+                        }
+                        MsiTablesDao::Component(_) => {}
+                    }
+                    removed.push(dao);
+                }
+                Ok(removed)
             }
         }
 
-        pub struct FeatureComponentDao {
-            feature_: FeatureIdentifier,
-            component_: ComponentIdentifier,
-        }
+        #[doc = "This is a simple wrapper around `Identifier` for the `DirectoryTable`. Used to ensure that identifiers for the `DirectoryTable` are only used in valid locations."]
+        #[derive(Clone, Debug, Default, PartialEq, Eq, Hash, ::whimsi_macros::IdentifierToValue)]
+        pub struct DirectoryIdentifier(::whimsi_lib::types::column::identifier::Identifier);
 
-        impl PrimaryIdentifier for FeatureComponentDao {
-            fn primary_identifier(&self) -> Option<Identifier> {
-                None
-            }
+        impl ::std::fmt::Display for DirectoryIdentifier {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) ->::std::fmt::Result { ::std::fmt::Display::fmt(&self.0, f) }
         }
 
-        impl MsiDao for FeatureComponentDao {
+        impl ::whimsi_lib::types::column::identifier::ToIdentifier for DirectoryIdentifier {
+            fn to_identifier(&self) ->::whimsi_lib::types::column::identifier::Identifier { self.0 }
+        }
 
-            fn conflicts_with(&self, other: &Self) -> bool {
-                self.feature_ == other.feature_ && self.component_ == other.component_
+        impl ::std::str::FromStr for DirectoryIdentifier {
+            type Err = <::whimsi_lib::types::column::identifier::Identifier as ::std::str::FromStr>::Err;
+            fn from_str(s: &str) ->::core::result::Result<Self, Self::Err> {
+                <::whimsi_lib::types::column::identifier::Identifier as ::std::str::FromStr>::from_str(s).map(Self)
             }
+        }
 
-            fn to_row(&self) -> Vec<msi::Value> {
-                vec![
-                    msi::ToValue::to_value(self.feature_),
-                    msi::ToValue::to_value(self.component_),
-                ]
+        impl ::core::convert::TryFrom<::msi::Value> for DirectoryIdentifier {
+            type Error = <::whimsi_lib::types::column::identifier::Identifier as ::core::convert::TryFrom<::msi::Value>>::Error;
+            fn try_from(value: ::msi::Value) ->::core::result::Result<Self, Self::Error> {
+                <::whimsi_lib::types::column::identifier::Identifier as ::core::convert::TryFrom<::msi::Value>>::try_from(value).map(Self)
             }
         }
 
-        pub struct FeatureComponentTable {
-            entries: Vec<FeatureComponentDao>,
+        #[doc = "Creates new `DirectoryIdentifier`s for the `DirectoryTable`. Identifiers are prefixed with `DIRECTORY` and never collide with an identifier in the shared `used` registry."]
+        #[derive(Clone, Debug, Default, PartialEq)]
+        pub struct DirectoryIdentifierGenerator {
+            count: usize,
+            used: ::std::rc::Rc<::std::cell::RefCell<::std::vec::Vec<::whimsi_lib::types::column::identifier::Identifier>>>,
+        }
+
+        impl ::whimsi_lib::types::helpers::id_generator::IdentifierGenerator for DirectoryIdentifierGenerator {
+            type IdentifierType = DirectoryIdentifier;
+            fn id_prefix(&self) -> &str { "DIRECTORY" }
+            fn used(&self) -> & ::std::rc::Rc<::std::cell::RefCell<::std::vec::Vec<::whimsi_lib::types::column::identifier::Identifier>>> {
+                &self.used
+            }
+            fn count(&self) -> usize { self.count }
+            fn count_mut(&mut self) -> &mut usize { &mut self.count }
+        }
+
+        impl ::core::convert::From<::std::rc::Rc<::std::cell::RefCell<::std::vec::Vec<::whimsi_lib::types::column::identifier::Identifier>>>> for DirectoryIdentifierGenerator {
+            fn from(used: ::std::rc::Rc<::std::cell::RefCell<::std::vec::Vec<::whimsi_lib::types::column::identifier::Identifier>>>) -> Self {
+                Self { count: 0, used }
+            }
+        }
+
+        #[doc = "Errors produced while creating a `DirectoryDao` or converting rows of the `Directory` table into one."]
+        #[derive(Clone, Debug, PartialEq)]
+        pub enum DirectoryError {
+            #[doc = r" The row does not contain the column."]
+            MissingColumn { column: &'static str },
+            #[doc = r" A builder was finished without setting a field that is not nullable."]
+            MissingField { field: &'static str },
+            #[doc = r" The value stored in the column could not be converted into the type of the field."]
+            InvalidValue {
+                column: &'static str,
+                value: ::std::string::String,
+                reason: ::std::string::String,
+            },
+            #[doc = r" The value breaks the `min`, `max` or `set` constraint of the column."]
+            NotAllowed {
+                column: &'static str,
+                value: ::std::string::String,
+                reason: ::std::string::String,
+            },
+            #[doc = r" The value of the field is longer than the `length` of its column."]
+            TooLong {
+                field: &'static str,
+                column: &'static str,
+                length: usize,
+                max_length: usize,
+            },
+            #[doc = r" The row has the same values as a row already in the table in columns that must be"]
+            #[doc = r" unique."]
+            Conflict { columns: ::std::vec::Vec< &'static str> },
+            #[doc = r" The value of a `unique_scope` column is already used by a row of a table that shares"]
+            #[doc = r" the registry."]
+            ScopeConflict {
+                column: &'static str,
+                scope: &'static str,
+                value: ::std::string::String,
+                table: &'static str,
+            },
+            #[doc = r" An `on_insert` or `on_remove` function of the table refused the change."]
+            Rejected { reason: ::std::string::String },
+        }
+
+        impl ::std::fmt::Display for DirectoryError {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) ->::std::fmt::Result {
+                match self {
+                    Self::MissingColumn { column } => {
+                        write!(f, "Table `Directory` has no column `{column}`", column = column)
+                    }
+                    Self::MissingField { field } => {
+                        write!(f, "Field `{field}` of `DirectoryDao` was never set", field = field)
+                    }
+                    Self::InvalidValue { column, value, reason } => write!(
+                        f,
+                        "Failed to convert value `{value}` in column `{column}` of table `Directory`: {reason}",
+                        column = column,
+                        value = value,
+                        reason = reason
+                    ),
+                    Self::NotAllowed { column, value, reason } => write!(
+                        f,
+                        "Value `{value}` is not allowed in column `{column}` of table `Directory`: {reason}",
+                        column = column,
+                        value = value,
+                        reason = reason
+                    ),
+                    Self::TooLong { field, column, length, max_length } => write!(
+                        f,
+                        "Value of field `{field}` is {length} characters long but column `{column}` of table `Directory` allows at most {max_length}",
+                        field = field,
+                        column = column,
+                        length = length,
+                        max_length = max_length
+                    ),
+                    Self::Conflict { columns } => {
+                        write!(
+                            f,
+                            "Row conflicts with an existing row of table `Directory` in columns {columns}",
+                            columns = columns.join(", ")
+                        )
+                    }
+                    Self::ScopeConflict { column, scope, value, table } => write!(
+                        f,
+                        "Value `{value}` of column `{column}` in table `Directory` is already used in scope `{scope}` by table `{table}`",
+                        column = column,
+                        scope = scope,
+                        value = value,
+                        table = table
+                    ),
+                    Self::Rejected { reason } => write!(
+                        f,
+                        "Row was rejected by table `Directory`: {reason}",
+                        reason = reason
+                    ),
+                }
+            }
+        }
+
+        impl ::std::error::Error for DirectoryError {}
+
+        #[derive(Clone, Debug, PartialEq)]
+        pub struct DirectoryDao {
+            directory: DirectoryIdentifier,
+            parent_directory: Option<DirectoryIdentifier>,
+            default_dir: DefaultDir,
+        }
+
+        impl DirectoryDao {
+            pub fn directory(&self) -> &DirectoryIdentifier { &self.directory }
+            pub fn parent_directory(&self) -> &Option<DirectoryIdentifier> {
+                &self.parent_directory
+            }
+            pub fn default_dir(&self) -> &DefaultDir { &self.default_dir }
+        }
+
+        impl DirectoryDao {
+            #[doc = r" Creates the row for callers whose values are already known to be valid. The values"]
+            #[doc = r" are not checked against their columns, use `try_new` for that."]
+            pub fn new(
+                directory: impl ::core::convert::Into<DirectoryIdentifier>,
+                parent_directory: impl ::core::convert::Into<Option<DirectoryIdentifier>>,
+                default_dir: impl ::core::convert::Into<DefaultDir>
+            ) -> DirectoryDao {
+                DirectoryDao {
+                    directory: directory.into(),
+                    parent_directory: parent_directory.into(),
+                    default_dir: default_dir.into(),
+                }
+            }
+
+            #[doc = r" Creates the row after checking that every value fits in its column. See"]
+            #[doc = r" `check_constraints`."]
+            pub fn try_new(
+                directory: impl ::core::convert::Into<DirectoryIdentifier>,
+                parent_directory: impl ::core::convert::Into<Option<DirectoryIdentifier>>,
+                default_dir: impl ::core::convert::Into<DefaultDir>
+            ) ->::core::result::Result<DirectoryDao, DirectoryError> {
+                let dao = Self::new(directory, parent_directory, default_dir);
+                dao.check_constraints()?;
+                Ok(dao)
+            }
+        }
+
+        impl DirectoryDao {
+            #[doc = r" Checks the value of every field against the `length`, `min`, `max` and `set` of its"]
+            #[doc = r" column."]
+            pub fn check_constraints(&self) ->::core::result::Result<(), DirectoryError> {
+                {
+                    let value = ::msi::ToValue::to_value(&self.directory);
+                    if let ::msi::Value::Str(text) = &value {
+                        let max_length = (72) as usize;
+                        let length = text.chars().count();
+                        if max_length != 0 && length > max_length {
+                            return Err(DirectoryError::TooLong {
+                                field: "directory",
+                                column: "Directory",
+                                length,
+                                max_length,
+                            });
+                        }
+                    }
+                }
+                {
+                    let value = match &self.parent_directory {
+                        Some(value) =>::msi::ToValue::to_value(value),
+                        None =>::msi::Value::Null,
+                    };
+                    if let ::msi::Value::Str(text) = &value {
+                        let max_length = (72) as usize;
+                        let length = text.chars().count();
+                        if max_length != 0 && length > max_length {
+                            return Err(DirectoryError::TooLong {
+                                field: "parent_directory",
+                                column: "Directory_Parent",
+                                length,
+                                max_length,
+                            });
+                        }
+                    }
+                }
+                {
+                    let value = ::msi::ToValue::to_value(&self.default_dir);
+                    if let ::msi::Value::Str(text) = &value {
+                        let max_length = (255) as usize;
+                        let length = text.chars().count();
+                        if max_length != 0 && length > max_length {
+                            return Err(DirectoryError::TooLong {
+                                field: "default_dir",
+                                column: "DefaultDir",
+                                length,
+                                max_length,
+                            });
+                        }
+                    }
+                }
+                Ok(())
+            }
+        }
+
+        impl DirectoryDao {
+            #[doc = "Reads a `DirectoryDao` from a row of the `Directory` table. Columns are looked up by name so the order of the columns in the row does not matter."]
+            pub fn from_row(row: & ::msi::Row) ->::core::result::Result<DirectoryDao, DirectoryError> {
+                let dao = DirectoryDao {
+                    directory: {
+                        if !row.has_column("Directory") {
+                            return Err(DirectoryError::MissingColumn { column: "Directory" });
+                        }
+                        let value = &row["Directory"];
+                        match <DirectoryIdentifier as ::core::convert::TryFrom<::msi::Value>>::try_from(value.clone()) {
+                            Ok(converted) => converted,
+                            Err(error) => {
+                                return Err(DirectoryError::InvalidValue {
+                                    column: "Directory",
+                                    value: value.to_string(),
+                                    reason: ::std::string::ToString::to_string(&error),
+                                });
+                            }
+                        }
+                    },
+                    parent_directory: {
+                        if !row.has_column("Directory_Parent") {
+                            return Err(DirectoryError::MissingColumn { column: "Directory_Parent" });
+                        }
+                        let value = &row["Directory_Parent"];
+                        if value.is_null() {
+                            None
+                        } else {
+                            Some(match <DirectoryIdentifier as ::core::convert::TryFrom<::msi::Value>>::try_from(value.clone()) {
+                                Ok(converted) => converted,
+                                Err(error) => {
+                                    return Err(DirectoryError::InvalidValue {
+                                        column: "Directory_Parent",
+                                        value: value.to_string(),
+                                        reason: ::std::string::ToString::to_string(&error),
+                                    });
+                                }
+                            })
+                        }
+                    },
+                    default_dir: {
+                        if !row.has_column("DefaultDir") {
+                            return Err(DirectoryError::MissingColumn { column: "DefaultDir" });
+                        }
+                        let value = &row["DefaultDir"];
+                        match <DefaultDir as ::core::convert::TryFrom<::msi::Value>>::try_from(value.clone()) {
+                            Ok(converted) => converted,
+                            Err(error) => {
+                                return Err(DirectoryError::InvalidValue {
+                                    column: "DefaultDir",
+                                    value: value.to_string(),
+                                    reason: ::std::string::ToString::to_string(&error),
+                                });
+                            }
+                        }
+                    },
+                };
+                dao.check_constraints()?;
+                Ok(dao)
+            }
+        }
+
+        impl ::core::convert::TryFrom<::msi::Row> for DirectoryDao {
+            type Error = DirectoryError;
+            fn try_from(row: ::msi::Row) ->::core::result::Result<Self, Self::Error> {
+                Self::from_row(&row)
+            }
+        }
+
+        impl ::whimsi_lib::tables::PrimaryIdentifier for DirectoryDao {
+            fn primary_identifier(&self) ->::core::option::Option<::whimsi_lib::types::column::identifier::Identifier> {
+                Some(::whimsi_lib::types::column::identifier::ToIdentifier::to_identifier(&self.directory))
+            }
+        }
+
+        impl DirectoryDao {
+            #[doc = r" The columns that hold the same values in both rows where the table requires them to"]
+            #[doc = r" be unique. Empty when both rows can be stored in the same table."]
+            pub fn conflicting_columns(&self, other: &Self) ->::std::vec::Vec< &'static str> {
+                let mut columns = ::std::vec::Vec::new();
+                if self.directory == other.directory {
+                    columns.extend(["Directory"]);
+                }
+                columns
+            }
+        }
+
+        #[doc = "The primary key of a row of the `Directory` table. Holds the `primary_key` fields in the order they are declared."]
+        #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+        pub struct DirectoryKey(pub DirectoryIdentifier);
+
+        impl ::core::convert::From<DirectoryIdentifier> for DirectoryKey {
+            fn from(value: DirectoryIdentifier) -> Self { DirectoryKey(value) }
+        }
+
+        impl DirectoryDao {
+            #[doc = r" The primary key of the row."]
+            pub fn key(&self) -> DirectoryKey { DirectoryKey(self.directory.clone()) }
+        }
+
+        impl ::whimsi_lib::tables::MsiDao for DirectoryDao {
+            fn conflicts_with(&self, other: &Self) -> bool { !self.conflicting_columns(other).is_empty() }
+            fn to_row(&self) ->::std::vec::Vec<::msi::Value> {
+                ::std::vec![
+                    ::msi::ToValue::to_value(&self.directory),
+                    match &self.parent_directory {
+                        Some(value) =>::msi::ToValue::to_value(value),
+                        None =>::msi::Value::Null,
+                    },
+                    ::msi::ToValue::to_value(&self.default_dir),
+                ]
+            }
+        }
+
+        #[doc = "Builds a `DirectoryDao` one named column at a time. Create one with `DirectoryDao::builder`."]
+        #[derive(Clone, Debug, Default)]
+        pub struct DirectoryDaoBuilder {
+            directory: ::core::option::Option<DirectoryIdentifier>,
+            parent_directory: Option<DirectoryIdentifier>,
+            default_dir: ::core::option::Option<DefaultDir>,
+        }
+
+        impl DirectoryDaoBuilder {
+            #[doc = "Sets the `Directory` column."]
+            pub fn directory(mut self, directory: impl ::core::convert::Into<DirectoryIdentifier>) -> Self {
+                self.directory = Some(directory.into());
+                self
+            }
+
+            #[doc = "Sets the `Directory_Parent` column."]
+            pub fn parent_directory(
+                mut self,
+                parent_directory: impl ::core::convert::Into<DirectoryIdentifier>
+            ) -> Self {
+                self.parent_directory = Some(parent_directory.into());
+                self
+            }
+
+            #[doc = "Sets the `DefaultDir` column."]
+            pub fn default_dir(mut self, default_dir: impl ::core::convert::Into<DefaultDir>) -> Self {
+                self.default_dir = Some(default_dir.into());
+                self
+            }
+
+            #[doc = r" Creates the row. Fails if a column that is not nullable was never set or a value"]
+            #[doc = r" does not fit in its column."]
+            pub fn build(self) ->::core::result::Result<DirectoryDao, DirectoryError> {
+                let dao = DirectoryDao {
+                    directory: self.directory.ok_or(DirectoryError::MissingField { field: "directory", })?,
+                    parent_directory: self.parent_directory,
+                    default_dir: self.default_dir.ok_or(DirectoryError::MissingField { field: "default_dir", })?,
+                };
+                dao.check_constraints()?;
+                Ok(dao)
+            }
+        }
+
+        impl DirectoryDao {
+            #[doc = r" Starts building a row with every nullable column set to `None`."]
+            pub fn builder() -> DirectoryDaoBuilder { DirectoryDaoBuilder::default() }
+        }
+
+        #[derive(Clone, Debug, Default)]
+        pub struct DirectoryTable {
+            generator: DirectoryIdentifierGenerator,
+            key_index: ::core::option::Option<::std::collections::HashMap<DirectoryKey, usize>>,
+            entries: ::std::vec::Vec<DirectoryDao>,
+        }
+
+        impl ::core::cmp::PartialEq for DirectoryTable {
+            fn eq(&self, other: &Self) -> bool {
+                self.entries == other.entries && self.generator == other.generator
+            }
+        }
+
+        impl DirectoryTable {
+            #[doc = "Creates an empty `DirectoryTable` whose generator never creates an identifier that is already in `used`. The identifiers of rows added to the table are recorded in `used` as well. Share `used` between tables so identifiers are unique across them."]
+            pub fn new(used: ::std::rc::Rc<::std::cell::RefCell<::std::vec::Vec<::whimsi_lib::types::column::identifier::Identifier>>>) -> DirectoryTable {
+                DirectoryTable {
+                    generator: used.into(),
+                    key_index: None,
+                    entries: ::std::vec::Vec::new(),
+                }
+            }
+
+            #[doc = r" The generator used to create identifiers for new rows in this table."]
+            pub fn generator_mut(&mut self) -> &mut DirectoryIdentifierGenerator { &mut self.generator }
+        }
+
+        impl DirectoryTable {
+            #[doc = r" The entry that `dao` cannot be stored next to because they share a primary key or a"]
+            #[doc = r" unique value. The primary key is looked up through the key index, `unique` columns"]
+            #[doc = r" and groups are compared with every entry."]
+            pub fn find_conflict(&self, dao: &DirectoryDao) ->::core::option::Option< &DirectoryDao> {
+                if let Some(position) = self.position_by_key(&dao.key()) {
+                    return Some(&self.entries[position]);
+                }
+                None
+            }
+
+            #[doc = r" Adds the row for callers that know it does not conflict with the table."]
+            #[doc = r""]
+            #[doc = r" # Panics"]
+            #[doc = r""]
+            #[doc = r" Panics if the row is refused. Use `try_insert` to handle this instead."]
+            pub fn insert(&mut self, dao: DirectoryDao) {
+                self.try_insert(dao).unwrap_or_else(|error| panic!("{error}"))
+            }
+
+            #[doc = r" Adds the row unless it conflicts with a row that is already in the table."]
+            pub fn try_insert(&mut self, dao: DirectoryDao) ->::core::result::Result<(), DirectoryError> {
+                if let Some(entry) = self.find_conflict(&dao) {
+                    return Err(DirectoryError::Conflict { columns: entry.conflicting_columns(&dao), });
+                }
+                self.claim_identifier(&dao);
+                let position = self.entries.len();
+                self.key_index_mut().insert(dao.key(), position);
+                self.entries.push(dao);
+                Ok(())
+            }
+
+            #[doc = r" Removes and returns the entry at `index`."]
+            #[doc = r""]
+            #[doc = r" # Panics"]
+            #[doc = r""]
+            #[doc = r" Panics if `index` is out of bounds."]
+            pub fn remove(&mut self, index: usize) ->::core::result::Result<DirectoryDao, DirectoryError> {
+                let dao = self.entries.remove(index);
+                if let Some(key_index) = &mut self.key_index {
+                    key_index.remove(&dao.key());
+                    for position in key_index.values_mut() {
+                        if *position > index {
+                            *position -= 1;
+                        }
+                    }
+                }
+                self.release_identifier(&dao);
+                Ok(dao)
+            }
+
+            #[doc = r" The position of the entry with the given primary key."]
+            fn position_by_key(&self, key: &DirectoryKey) ->::core::option::Option<usize> {
+                match &self.key_index {
+                    Some(key_index) => key_index.get(key).copied(),
+                    None => self.entries.iter().position(|entry| entry.key() == *key),
+                }
+            }
+
+            #[doc = r" The position of every entry by its primary key. Rebuilt from the entries if it was"]
+            #[doc = r" dropped."]
+            fn key_index_mut(&mut self) -> &mut ::std::collections::HashMap<DirectoryKey, usize> {
+                let entries = &self.entries;
+                self.key_index.get_or_insert_with(| | {
+                    entries.iter().enumerate().map(|(position, entry)| (entry.key(), position)).collect()
+                })
+            }
+
+            #[doc = r" Adds the row or replaces the entry with the same primary key. Returns the replaced"]
+            #[doc = r" entry. Fails if the row conflicts with any other entry. Like `find_conflict` this"]
+            #[doc = r" compares the `unique` values of the row with every entry."]
+            pub fn upsert(&mut self, dao: DirectoryDao) ->::core::result::Result<::core::option::Option<DirectoryDao>, DirectoryError> {
+                let key = dao.key();
+                let index = self.position_by_key(&key);
+                Ok(match index {
+                    Some(index) => Some(::std::mem::replace(&mut self.entries[index], dao)),
+                    None => {
+                        self.claim_identifier(&dao);
+                        let position = self.entries.len();
+                        self.key_index_mut().insert(key, position);
+                        self.entries.push(dao);
+                        None
+                    }
+                })
+            }
+
+            #[doc = r" The entry with the given primary key."]
+            pub fn get(&self, key: &DirectoryKey) ->::core::option::Option< &DirectoryDao> {
+                self.position_by_key(key).map(|position| &self.entries[position])
+            }
+
+            #[doc = r" The entry with the given primary key. The primary key of the entry must not be changed"]
+            #[doc = r" through the reference as the table would no longer find it by its key."]
+            pub fn get_mut(&mut self, key: &DirectoryKey) ->::core::option::Option< &mut DirectoryDao> {
+                self.position_by_key(key).map(|position| &mut self.entries[position])
+            }
+
+            #[doc = r" Whether an entry has the given primary key."]
+            pub fn contains_key(&self, key: &DirectoryKey) -> bool { self.position_by_key(key).is_some() }
+
+            #[doc = r" The primary key of every entry in the order of the entries."]
+            pub fn primary_keys(&self) -> impl ::core::iter::Iterator<Item = DirectoryKey> + '_ { self.entries.iter().map(DirectoryDao::key) }
+
+            #[doc = r" Removes and returns the entry with the given primary key, if there is one."]
+            pub fn remove_by_key(&mut self, key: &DirectoryKey,) ->::core::result::Result<::core::option::Option<DirectoryDao>, DirectoryError> {
+                match self.position_by_key(key) {
+                    Some(index) => self.remove(index).map(Some),
+                    None => Ok(None),
+                }
+            }
+
+            #[doc = "The entries whose `Directory_Parent` column holds `value`."]
+            pub fn find_by_parent_directory<'a> (&'a self, value: &'a DirectoryIdentifier,) -> impl ::core::iter::Iterator<Item = &'a DirectoryDao> + 'a {
+                self.entries.iter().filter(move |entry| entry.parent_directory.as_ref() == Some(value))
+            }
+
+            #[doc = r" Records the identifier of `dao` as used so the generator does not create it again."]
+            fn claim_identifier(&self, dao: &DirectoryDao) {
+                let identifier = ::whimsi_lib::types::column::identifier::ToIdentifier::to_identifier(&dao.directory);
+                let mut used = ::whimsi_lib::types::helpers::id_generator::IdentifierGenerator::used(&self.generator).borrow_mut();
+                if !used.contains(&identifier) {
+                    used.push(identifier);
+                }
+            }
+
+            #[doc = r" Frees the identifier of `dao` so the generator may create it again."]
+            fn release_identifier(&self, dao: &DirectoryDao) {
+                let identifier = ::whimsi_lib::types::column::identifier::ToIdentifier::to_identifier(&dao.directory);
+                ::whimsi_lib::types::helpers::id_generator::IdentifierGenerator::used(&self.generator).borrow_mut().retain(|used| *used != identifier);
+            }
+        }
+
+        impl <K: ::core::convert::Into<DirectoryKey>>::std::ops::Index<K> for DirectoryTable {
+            type Output = DirectoryDao;
+            #[doc = r" The entry with the given primary key."]
+            #[doc = r""]
+            #[doc = r" # Panics"]
+            #[doc = r""]
+            #[doc = r" Panics if no entry has the key. Use `get` to handle this instead."]
+            fn index(&self, key: K) -> &DirectoryDao {
+                let key = key.into();
+                self.get(&key).unwrap_or_else(| |panic!("No entry with key {key:?}"))
+            }
+        }
+
+        impl ::whimsi_lib::tables::MsiTableKind for DirectoryTable {
+            type TableValue = DirectoryDao;
+            fn name(&self) -> &'static str { "Directory" }
+            fn entries(&self) -> & ::std::vec::Vec<DirectoryDao> {
+                &self.entries
+            }
+            fn entries_mut(&mut self) -> &mut ::std::vec::Vec<DirectoryDao> {
+                self.key_index = None;
+                &mut self.entries
+            }
+            fn primary_key_indices(&self) ->::std::vec::Vec<usize> {
+                ::std::vec![0usize,]
+            }
+            fn columns(&self) ->::std::vec::Vec<::msi::Column> {
+                ::std::vec![
+                    ::msi::Column::build("Directory").primary_key().category(msi::Category::Identifier).string(72),
+                    ::msi::Column::build("Directory_Parent").nullable().foreign_key("Directory", 0).category(msi::Category::Identifier).string(72),
+                    ::msi::Column::build("DefaultDir").localizable().category(msi::Category::DefaultDir).string(255),
+                ]
+            }
+        }
+
+        impl DirectoryTable {
+            #[doc = "The rows of the `_Validation` table that describe the columns of the `Directory` table."]
+            pub fn validation_rows() ->::std::vec::Vec<::std::vec::Vec<::msi::Value>> {
+                ::std::vec![
+                    ::std::vec![
+                        ::msi::Value::Str(::std::string::String::from("Directory")),
+                        ::msi::Value::Str(::std::string::String::from("Directory")),
+                        ::msi::Value::Str(::std::string::String::from("N")),
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                        ::msi::Value::Str(::std::string::String::from("Identifier")),
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                    ],
+                    ::std::vec![
+                        ::msi::Value::Str(::std::string::String::from("Directory")),
+                        ::msi::Value::Str(::std::string::String::from("Directory_Parent")),
+                        ::msi::Value::Str(::std::string::String::from("Y")),
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                        ::msi::Value::Str(::std::string::String::from("Directory")),
+                        ::msi::Value::Int(1i32),
+                        ::msi::Value::Str(::std::string::String::from("Identifier")),
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                    ],
+                    ::std::vec![
+                        ::msi::Value::Str(::std::string::String::from("Directory")),
+                        ::msi::Value::Str(::std::string::String::from("DefaultDir")),
+                        ::msi::Value::Str(::std::string::String::from("N")),
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                        ::msi::Value::Str(::std::string::String::from("DefaultDir")),
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                    ],
+                ]
+            }
+        }
+
+        #[doc = "This is a simple wrapper around `Identifier` for the `ComponentTable`. Used to ensure that identifiers for the `ComponentTable` are only used in valid locations."]
+        #[derive(Clone, Debug, Default, PartialEq, Eq, Hash, ::whimsi_macros::IdentifierToValue)]
+        pub struct ComponentIdentifier(::whimsi_lib::types::column::identifier::Identifier);
+
+        impl ::std::fmt::Display for ComponentIdentifier {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) ->::std::fmt::Result { ::std::fmt::Display::fmt(&self.0, f) }
+        }
+
+        impl ::whimsi_lib::types::column::identifier::ToIdentifier for ComponentIdentifier {
+            fn to_identifier(&self) ->::whimsi_lib::types::column::identifier::Identifier { self.0 }
+        }
+
+        impl ::std::str::FromStr for ComponentIdentifier {
+            type Err = <::whimsi_lib::types::column::identifier::Identifier as ::std::str::FromStr>::Err;
+            fn from_str(s: &str) ->::core::result::Result<Self, Self::Err> {
+                <::whimsi_lib::types::column::identifier::Identifier as ::std::str::FromStr>::from_str(s).map(Self)
+            }
+        }
+
+        impl ::core::convert::TryFrom<::msi::Value> for ComponentIdentifier {
+            type Error = <::whimsi_lib::types::column::identifier::Identifier as ::core::convert::TryFrom<::msi::Value>>::Error;
+            fn try_from(value: ::msi::Value) ->::core::result::Result<Self, Self::Error> {
+                <::whimsi_lib::types::column::identifier::Identifier as ::core::convert::TryFrom<::msi::Value>>::try_from(value).map(Self)
+            }
+        }
+
+        #[doc = "Creates new `ComponentIdentifier`s for the `ComponentTable`. Identifiers are prefixed with `COMPONENT` and never collide with an identifier in the shared `used` registry."]
+        #[derive(Clone, Debug, Default, PartialEq)]
+        pub struct ComponentIdentifierGenerator {
+            count: usize,
+            used: ::std::rc::Rc<::std::cell::RefCell<::std::vec::Vec<::whimsi_lib::types::column::identifier::Identifier>>>,
+        }
+
+        impl ::whimsi_lib::types::helpers::id_generator::IdentifierGenerator for ComponentIdentifierGenerator {
+            type IdentifierType = ComponentIdentifier;
+            fn id_prefix(&self) -> &str { "COMPONENT" }
+            fn used(&self) -> & ::std::rc::Rc<::std::cell::RefCell<::std::vec::Vec<::whimsi_lib::types::column::identifier::Identifier>>> {
+                &self.used
+            }
+            fn count(&self) -> usize { self.count }
+            fn count_mut(&mut self) -> &mut usize { &mut self.count }
+        }
+
+        impl ::core::convert::From<::std::rc::Rc<::std::cell::RefCell<::std::vec::Vec<::whimsi_lib::types::column::identifier::Identifier>>>> for ComponentIdentifierGenerator {
+            fn from(used: ::std::rc::Rc<::std::cell::RefCell<::std::vec::Vec<::whimsi_lib::types::column::identifier::Identifier>>>) -> Self {
+                Self { count: 0, used }
+            }
+        }
+
+        #[doc = "Errors produced while creating a `ComponentDao` or converting rows of the `Component` table into one."]
+        #[derive(Clone, Debug, PartialEq)]
+        pub enum ComponentError {
+            #[doc = r" The row does not contain the column."]
+            MissingColumn { column: &'static str },
+            #[doc = r" A builder was finished without setting a field that is not nullable."]
+            MissingField { field: &'static str },
+            #[doc = r" The value stored in the column could not be converted into the type of the field."]
+            InvalidValue {
+                column: &'static str,
+                value: ::std::string::String,
+                reason: ::std::string::String,
+            },
+            #[doc = r" The value breaks the `min`, `max` or `set` constraint of the column."]
+            NotAllowed {
+                column: &'static str,
+                value: ::std::string::String,
+                reason: ::std::string::String,
+            },
+            #[doc = r" The value of the field is longer than the `length` of its column."]
+            TooLong {
+                field: &'static str,
+                column: &'static str,
+                length: usize,
+                max_length: usize,
+            },
+            #[doc = r" The row has the same values as a row already in the table in columns that must be"]
+            #[doc = r" unique."]
+            Conflict { columns: ::std::vec::Vec< &'static str> },
+            #[doc = r" The value of a `unique_scope` column is already used by a row of a table that shares"]
+            #[doc = r" the registry."]
+            ScopeConflict {
+                column: &'static str,
+                scope: &'static str,
+                value: ::std::string::String,
+                table: &'static str,
+            },
+            #[doc = r" An `on_insert` or `on_remove` function of the table refused the change."]
+            Rejected { reason: ::std::string::String },
+        }
+
+        impl ::std::fmt::Display for ComponentError {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) ->::std::fmt::Result {
+                match self {
+                    Self::MissingColumn { column } => {
+                        write!(f, "Table `Component` has no column `{column}`", column = column)
+                    }
+                    Self::MissingField { field } => {
+                        write!(f, "Field `{field}` of `ComponentDao` was never set", field = field)
+                    }
+                    Self::InvalidValue { column, value, reason } => write!(
+                        f,
+                        "Failed to convert value `{value}` in column `{column}` of table `Component`: {reason}",
+                        column = column,
+                        value = value,
+                        reason = reason
+                    ),
+                    Self::NotAllowed { column, value, reason } => write!(
+                        f,
+                        "Value `{value}` is not allowed in column `{column}` of table `Component`: {reason}",
+                        column = column,
+                        value = value,
+                        reason = reason
+                    ),
+                    Self::TooLong { field, column, length, max_length } => write!(
+                        f,
+                        "Value of field `{field}` is {length} characters long but column `{column}` of table `Component` allows at most {max_length}",
+                        field = field,
+                        column = column,
+                        length = length,
+                        max_length = max_length
+                    ),
+                    Self::Conflict { columns } => {
+                        write!(
+                            f,
+                            "Row conflicts with an existing row of table `Component` in columns {columns}",
+                            columns = columns.join(", ")
+                        )
+                    }
+                    Self::ScopeConflict { column, scope, value, table } => write!(
+                        f,
+                        "Value `{value}` of column `{column}` in table `Component` is already used in scope `{scope}` by table `{table}`",
+                        column = column,
+                        scope = scope,
+                        value = value,
+                        table = table
+                    ),
+                    Self::Rejected { reason } => write!(
+                        f,
+                        "Row was rejected by table `Component`: {reason}",
+                        reason = reason
+                    ),
+                }
+            }
+        }
+
+        impl ::std::error::Error for ComponentError {}
+
+        #[derive(Clone, Debug, PartialEq)]
+        pub struct ComponentDao {
+            component: ComponentIdentifier,
+            directory_: DirectoryIdentifier,
+            attributes: i16,
+        }
+
+        impl ComponentDao {
+            pub fn component(&self) -> &ComponentIdentifier { &self.component }
+            pub fn directory_(&self) -> &DirectoryIdentifier { &self.directory_ }
+            pub fn attributes(&self) -> &i16 { &self.attributes }
+        }
+
+        impl ComponentDao {
+            #[doc = r" Creates the row for callers whose values are already known to be valid. The values"]
+            #[doc = r" are not checked against their columns, use `try_new` for that."]
+            pub fn new(
+                component: impl ::core::convert::Into<ComponentIdentifier>,
+                directory_: impl ::core::convert::Into<DirectoryIdentifier>,
+                attributes: impl ::core::convert::Into<i16>
+            ) -> ComponentDao {
+                ComponentDao {
+                    component: component.into(),
+                    directory_: directory_.into(),
+                    attributes: attributes.into(),
+                }
+            }
+
+            #[doc = r" Creates the row after checking that every value fits in its column. See"]
+            #[doc = r" `check_constraints`."]
+            pub fn try_new(
+                component: impl ::core::convert::Into<ComponentIdentifier>,
+                directory_: impl ::core::convert::Into<DirectoryIdentifier>,
+                attributes: impl ::core::convert::Into<i16>
+            ) ->::core::result::Result<ComponentDao, ComponentError> {
+                let dao = Self::new(component, directory_, attributes);
+                dao.check_constraints()?;
+                Ok(dao)
+            }
+        }
+
+        impl ComponentDao {
+            #[doc = r" Checks the value of every field against the `length`, `min`, `max` and `set` of its"]
+            #[doc = r" column."]
+            pub fn check_constraints(&self) ->::core::result::Result<(), ComponentError> {
+                {
+                    let value = ::msi::ToValue::to_value(&self.component);
+                    if let ::msi::Value::Str(text) = &value {
+                        let max_length = (72) as usize;
+                        let length = text.chars().count();
+                        if max_length != 0 && length > max_length {
+                            return Err(ComponentError::TooLong {
+                                field: "component",
+                                column: "Component",
+                                length,
+                                max_length,
+                            });
+                        }
+                    }
+                }
+                {
+                    let value = ::msi::ToValue::to_value(&self.directory_);
+                    if let ::msi::Value::Str(text) = &value {
+                        let max_length = (72) as usize;
+                        let length = text.chars().count();
+                        if max_length != 0 && length > max_length {
+                            return Err(ComponentError::TooLong {
+                                field: "directory_",
+                                column: "Directory_",
+                                length,
+                                max_length,
+                            });
+                        }
+                    }
+                }
+                Ok(())
+            }
+        }
+
+        impl ComponentDao {
+            #[doc = "Reads a `ComponentDao` from a row of the `Component` table. Columns are looked up by name so the order of the columns in the row does not matter."]
+            pub fn from_row(row: & ::msi::Row) ->::core::result::Result<ComponentDao, ComponentError> {
+                let dao = ComponentDao {
+                    component: {
+                        if !row.has_column("Component") {
+                            return Err(ComponentError::MissingColumn { column: "Component" });
+                        }
+                        let value = &row["Component"];
+                        match <ComponentIdentifier as ::core::convert::TryFrom<::msi::Value>>::try_from(value.clone()) {
+                            Ok(converted) => converted,
+                            Err(error) => {
+                                return Err(ComponentError::InvalidValue {
+                                    column: "Component",
+                                    value: value.to_string(),
+                                    reason: ::std::string::ToString::to_string(&error),
+                                });
+                            }
+                        }
+                    },
+                    directory_: {
+                        if !row.has_column("Directory_") {
+                            return Err(ComponentError::MissingColumn { column: "Directory_" });
+                        }
+                        let value = &row["Directory_"];
+                        match <DirectoryIdentifier as ::core::convert::TryFrom<::msi::Value>>::try_from(value.clone()) {
+                            Ok(converted) => converted,
+                            Err(error) => {
+                                return Err(ComponentError::InvalidValue {
+                                    column: "Directory_",
+                                    value: value.to_string(),
+                                    reason: ::std::string::ToString::to_string(&error),
+                                });
+                            }
+                        }
+                    },
+                    attributes: {
+                        if !row.has_column("Attributes") {
+                            return Err(ComponentError::MissingColumn { column: "Attributes" });
+                        }
+                        let value = &row["Attributes"];
+                        match <i16 as ::core::convert::TryFrom<::msi::Value>>::try_from(value.clone()) {
+                            Ok(converted) => converted,
+                            Err(error) => {
+                                return Err(ComponentError::InvalidValue {
+                                    column: "Attributes",
+                                    value: value.to_string(),
+                                    reason: ::std::string::ToString::to_string(&error),
+                                });
+                            }
+                        }
+                    },
+                };
+                dao.check_constraints()?;
+                Ok(dao)
+            }
+        }
+
+        impl ::core::convert::TryFrom<::msi::Row> for ComponentDao {
+            type Error = ComponentError;
+            fn try_from(row: ::msi::Row) ->::core::result::Result<Self, Self::Error> {
+                Self::from_row(&row)
+            }
+        }
+
+        impl ::whimsi_lib::tables::PrimaryIdentifier for ComponentDao {
+            fn primary_identifier(&self) ->::core::option::Option<::whimsi_lib::types::column::identifier::Identifier> {
+                Some(::whimsi_lib::types::column::identifier::ToIdentifier::to_identifier(&self.component))
+            }
+        }
+
+        impl ComponentDao {
+            #[doc = r" The columns that hold the same values in both rows where the table requires them to"]
+            #[doc = r" be unique. Empty when both rows can be stored in the same table."]
+            pub fn conflicting_columns(&self, other: &Self) ->::std::vec::Vec< &'static str> {
+                let mut columns = ::std::vec::Vec::new();
+                if self.component == other.component {
+                    columns.extend(["Component"]);
+                }
+                columns
+            }
+        }
+
+        #[doc = "The primary key of a row of the `Component` table. Holds the `primary_key` fields in the order they are declared."]
+        #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+        pub struct ComponentKey(pub ComponentIdentifier);
+
+        impl ::core::convert::From<ComponentIdentifier> for ComponentKey {
+            fn from(value: ComponentIdentifier) -> Self { ComponentKey(value) }
+        }
+
+        impl ComponentDao {
+            #[doc = r" The primary key of the row."]
+            pub fn key(&self) -> ComponentKey { ComponentKey(self.component.clone()) }
+        }
+
+        impl ::whimsi_lib::tables::MsiDao for ComponentDao {
+            fn conflicts_with(&self, other: &Self) -> bool { !self.conflicting_columns(other).is_empty() }
+            fn to_row(&self) ->::std::vec::Vec<::msi::Value> {
+                ::std::vec![
+                    ::msi::ToValue::to_value(&self.component),
+                    ::msi::ToValue::to_value(&self.directory_),
+                    ::msi::ToValue::to_value(&self.attributes),
+                ]
+            }
+        }
+
+        #[doc = "Builds a `ComponentDao` one named column at a time. Create one with `ComponentDao::builder`."]
+        #[derive(Clone, Debug, Default)]
+        pub struct ComponentDaoBuilder {
+            component: ::core::option::Option<ComponentIdentifier>,
+            directory_: ::core::option::Option<DirectoryIdentifier>,
+            attributes: ::core::option::Option<i16>,
+        }
+
+        impl ComponentDaoBuilder {
+            #[doc = "Sets the `Component` column."]
+            pub fn component(mut self, component: impl ::core::convert::Into<ComponentIdentifier>) -> Self {
+                self.component = Some(component.into());
+                self
+            }
+
+            #[doc = "Sets the `Directory_` column."]
+            pub fn directory_(mut self, directory_: impl ::core::convert::Into<DirectoryIdentifier>) -> Self {
+                self.directory_ = Some(directory_.into());
+                self
+            }
+
+            #[doc = "Sets the `Attributes` column."]
+            pub fn attributes(mut self, attributes: impl ::core::convert::Into<i16>) -> Self {
+                self.attributes = Some(attributes.into());
+                self
+            }
+
+            #[doc = r" Creates the row. Fails if a column that is not nullable was never set or a value"]
+            #[doc = r" does not fit in its column."]
+            pub fn build(self) ->::core::result::Result<ComponentDao, ComponentError> {
+                let dao = ComponentDao {
+                    component: self.component.ok_or(ComponentError::MissingField { field: "component", })?,
+                    directory_: self.directory_.ok_or(ComponentError::MissingField { field: "directory_", })?,
+                    attributes: self.attributes.ok_or(ComponentError::MissingField { field: "attributes", })?,
+                };
+                dao.check_constraints()?;
+                Ok(dao)
+            }
+        }
+
+        impl ComponentDao {
+            #[doc = r" Starts building a row with every nullable column set to `None`."]
+            pub fn builder() -> ComponentDaoBuilder { ComponentDaoBuilder::default() }
+        }
+
+        #[derive(Clone, Debug, Default)]
+        pub struct ComponentTable {
+            generator: ComponentIdentifierGenerator,
+            key_index: ::core::option::Option<::std::collections::HashMap<ComponentKey, usize>>,
+            entries: ::std::vec::Vec<ComponentDao>,
+        }
+
+        impl ::core::cmp::PartialEq for ComponentTable {
+            fn eq(&self, other: &Self) -> bool {
+                self.entries == other.entries && self.generator == other.generator
+            }
+        }
+
+        impl ComponentTable {
+            #[doc = "Creates an empty `ComponentTable` whose generator never creates an identifier that is already in `used`. The identifiers of rows added to the table are recorded in `used` as well. Share `used` between tables so identifiers are unique across them."]
+            pub fn new(used: ::std::rc::Rc<::std::cell::RefCell<::std::vec::Vec<::whimsi_lib::types::column::identifier::Identifier>>>) -> ComponentTable {
+                ComponentTable {
+                    generator: used.into(),
+                    key_index: None,
+                    entries: ::std::vec::Vec::new(),
+                }
+            }
+
+            #[doc = r" The generator used to create identifiers for new rows in this table."]
+            pub fn generator_mut(&mut self) -> &mut ComponentIdentifierGenerator { &mut self.generator }
+        }
+
+        impl ComponentTable {
+            #[doc = r" The entry that `dao` cannot be stored next to because they share a primary key or a"]
+            #[doc = r" unique value. The primary key is looked up through the key index, `unique` columns"]
+            #[doc = r" and groups are compared with every entry."]
+            pub fn find_conflict(&self, dao: &ComponentDao) ->::core::option::Option< &ComponentDao> {
+                if let Some(position) = self.position_by_key(&dao.key()) {
+                    return Some(&self.entries[position]);
+                }
+                None
+            }
+
+            #[doc = r" Adds the row for callers that know it does not conflict with the table."]
+            #[doc = r""]
+            #[doc = r" # Panics"]
+            #[doc = r""]
+            #[doc = r" Panics if the row is refused. Use `try_insert` to handle this instead."]
+            pub fn insert(&mut self, dao: ComponentDao) {
+                self.try_insert(dao).unwrap_or_else(|error| panic!("{error}"))
+            }
+
+            #[doc = r" Adds the row unless it conflicts with a row that is already in the table."]
+            pub fn try_insert(&mut self, dao: ComponentDao) ->::core::result::Result<(), ComponentError> {
+                if let Some(entry) = self.find_conflict(&dao) {
+                    return Err(ComponentError::Conflict { columns: entry.conflicting_columns(&dao), });
+                }
+                self.claim_identifier(&dao);
+                let position = self.entries.len();
+                self.key_index_mut().insert(dao.key(), position);
+                self.entries.push(dao);
+                Ok(())
+            }
+
+            #[doc = r" Removes and returns the entry at `index`."]
+            #[doc = r""]
+            #[doc = r" # Panics"]
+            #[doc = r""]
+            #[doc = r" Panics if `index` is out of bounds."]
+            pub fn remove(&mut self, index: usize) ->::core::result::Result<ComponentDao, ComponentError> {
+                let dao = self.entries.remove(index);
+                if let Some(key_index) = &mut self.key_index {
+                    key_index.remove(&dao.key());
+                    for position in key_index.values_mut() {
+                        if *position > index {
+                            *position -= 1;
+                        }
+                    }
+                }
+                self.release_identifier(&dao);
+                Ok(dao)
+            }
+
+            #[doc = r" The position of the entry with the given primary key."]
+            fn position_by_key(&self, key: &ComponentKey) ->::core::option::Option<usize> {
+                match &self.key_index {
+                    Some(key_index) => key_index.get(key).copied(),
+                    None => self.entries.iter().position(|entry| entry.key() == *key),
+                }
+            }
+
+            #[doc = r" The position of every entry by its primary key. Rebuilt from the entries if it was"]
+            #[doc = r" dropped."]
+            fn key_index_mut(&mut self) -> &mut ::std::collections::HashMap<ComponentKey, usize> {
+                let entries = &self.entries;
+                self.key_index.get_or_insert_with(| | {
+                    entries.iter().enumerate().map(|(position, entry)| (entry.key(), position)).collect()
+                })
+            }
+
+            #[doc = r" Adds the row or replaces the entry with the same primary key. Returns the replaced"]
+            #[doc = r" entry. Fails if the row conflicts with any other entry. Like `find_conflict` this"]
+            #[doc = r" compares the `unique` values of the row with every entry."]
+            pub fn upsert(&mut self, dao: ComponentDao) ->::core::result::Result<::core::option::Option<ComponentDao>, ComponentError> {
+                let key = dao.key();
+                let index = self.position_by_key(&key);
+                Ok(match index {
+                    Some(index) => Some(::std::mem::replace(&mut self.entries[index], dao)),
+                    None => {
+                        self.claim_identifier(&dao);
+                        let position = self.entries.len();
+                        self.key_index_mut().insert(key, position);
+                        self.entries.push(dao);
+                        None
+                    }
+                })
+            }
+
+            #[doc = r" The entry with the given primary key."]
+            pub fn get(&self, key: &ComponentKey) ->::core::option::Option< &ComponentDao> {
+                self.position_by_key(key).map(|position| &self.entries[position])
+            }
+
+            #[doc = r" The entry with the given primary key. The primary key of the entry must not be changed"]
+            #[doc = r" through the reference as the table would no longer find it by its key."]
+            pub fn get_mut(&mut self, key: &ComponentKey) ->::core::option::Option< &mut ComponentDao> {
+                self.position_by_key(key).map(|position| &mut self.entries[position])
+            }
+
+            #[doc = r" Whether an entry has the given primary key."]
+            pub fn contains_key(&self, key: &ComponentKey) -> bool { self.position_by_key(key).is_some() }
+
+            #[doc = r" The primary key of every entry in the order of the entries."]
+            pub fn primary_keys(&self) -> impl ::core::iter::Iterator<Item = ComponentKey> + '_ { self.entries.iter().map(ComponentDao::key) }
+
+            #[doc = r" Removes and returns the entry with the given primary key, if there is one."]
+            pub fn remove_by_key(&mut self, key: &ComponentKey,) ->::core::result::Result<::core::option::Option<ComponentDao>, ComponentError> {
+                match self.position_by_key(key) {
+                    Some(index) => self.remove(index).map(Some),
+                    None => Ok(None),
+                }
+            }
+
+            #[doc = "The entries whose `Directory_` column holds `value`."]
+            pub fn find_by_directory_<'a> (&'a self, value: &'a DirectoryIdentifier,) -> impl ::core::iter::Iterator<Item = &'a ComponentDao> + 'a {
+                self.entries.iter().filter(move |entry| Some(&entry.directory_) == Some(value))
+            }
+
+            #[doc = r" Records the identifier of `dao` as used so the generator does not create it again."]
+            fn claim_identifier(&self, dao: &ComponentDao) {
+                let identifier = ::whimsi_lib::types::column::identifier::ToIdentifier::to_identifier(&dao.component);
+                let mut used = ::whimsi_lib::types::helpers::id_generator::IdentifierGenerator::used(&self.generator).borrow_mut();
+                if !used.contains(&identifier) {
+                    used.push(identifier);
+                }
+            }
+
+            #[doc = r" Frees the identifier of `dao` so the generator may create it again."]
+            fn release_identifier(&self, dao: &ComponentDao) {
+                let identifier = ::whimsi_lib::types::column::identifier::ToIdentifier::to_identifier(&dao.component);
+                ::whimsi_lib::types::helpers::id_generator::IdentifierGenerator::used(&self.generator).borrow_mut().retain(|used| *used != identifier);
+            }
+        }
+
+        impl <K: ::core::convert::Into<ComponentKey>>::std::ops::Index<K> for ComponentTable {
+            type Output = ComponentDao;
+            #[doc = r" The entry with the given primary key."]
+            #[doc = r""]
+            #[doc = r" # Panics"]
+            #[doc = r""]
+            #[doc = r" Panics if no entry has the key. Use `get` to handle this instead."]
+            fn index(&self, key: K) -> &ComponentDao {
+                let key = key.into();
+                self.get(&key).unwrap_or_else(| |panic!("No entry with key {key:?}"))
+            }
+        }
+
+        impl ::whimsi_lib::tables::MsiTableKind for ComponentTable {
+            type TableValue = ComponentDao;
+            fn name(&self) -> &'static str { "Component" }
+            fn entries(&self) -> & ::std::vec::Vec<ComponentDao> {
+                &self.entries
+            }
+            fn entries_mut(&mut self) -> &mut ::std::vec::Vec<ComponentDao> {
+                self.key_index = None;
+                &mut self.entries
+            }
+            fn primary_key_indices(&self) ->::std::vec::Vec<usize> {
+                ::std::vec![0usize,]
+            }
+            fn columns(&self) ->::std::vec::Vec<::msi::Column> {
+                ::std::vec![
+                    ::msi::Column::build("Component").primary_key().category(msi::Category::Identifier).string(72),
+                    ::msi::Column::build("Directory_").foreign_key("Directory", 0).category(msi::Category::Identifier).string(72),
+                    ::msi::Column::build("Attributes").category(msi::Category::Integer).int16(),
+                ]
+            }
+        }
+
+        impl ComponentTable {
+            #[doc = "The rows of the `_Validation` table that describe the columns of the `Component` table."]
+            pub fn validation_rows() ->::std::vec::Vec<::std::vec::Vec<::msi::Value>> {
+                ::std::vec![
+                    ::std::vec![
+                        ::msi::Value::Str(::std::string::String::from("Component")),
+                        ::msi::Value::Str(::std::string::String::from("Component")),
+                        ::msi::Value::Str(::std::string::String::from("N")),
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                        ::msi::Value::Str(::std::string::String::from("Identifier")),
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                    ],
+                    ::std::vec![
+                        ::msi::Value::Str(::std::string::String::from("Component")),
+                        ::msi::Value::Str(::std::string::String::from("Directory_")),
+                        ::msi::Value::Str(::std::string::String::from("N")),
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                        ::msi::Value::Str(::std::string::String::from("Directory")),
+                        ::msi::Value::Int(1i32),
+                        ::msi::Value::Str(::std::string::String::from("Identifier")),
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                    ],
+                    ::std::vec![
+                        ::msi::Value::Str(::std::string::String::from("Component")),
+                        ::msi::Value::Str(::std::string::String::from("Attributes")),
+                        ::msi::Value::Str(::std::string::String::from("N")),
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                        ::msi::Value::Null,
+                    ],
+                ]
+            }
+        }
+    };
+
+    // Compare the generated output with the expected output (e.g., using syn and comparing ASTs)
+    let parsed_output = syn::parse2::<syn::File>(output.clone())
+        .unwrap_or_else(|_| panic!("Failed to parse output of test data:\n{}", output));
+    let parsed_expected =
+        syn::parse2::<syn::File>(expected_output).expect("Failed to parse reference test data");
+
+    assert_eq!(
+        parsed_output.to_token_stream().to_string(),
+        parsed_expected.to_token_stream().to_string()
+    );
+}
+
+#[test]
+fn test_msi_table_from_row_reads_columns_by_name() {
+    let input = quote! {
+        #[msi_table(name = "Media")]
+        struct Media {
+            #[msi_column(primary_key, category = msi::Category::Integer)]
+            disk_id: i16,
+            #[msi_column(column_name = "Cabinet", category = msi::Category::Cabinet, length = 255)]
+            cabinet: Option<String>,
+        }
+    };
+
+    let output = msi_tables::gen_tables_impl(input).to_string();
+
+    // Columns are found by their name in the row rather than by their position.
+    let disk_id = quote! {
+        disk_id: {
+            if !row.has_column("DiskId") {
+                return Err(MediaError::MissingColumn { column: "DiskId" });
+            }
+            let value = &row["DiskId"];
+            match <i16 as ::core::convert::TryFrom<::msi::Value>>::try_from(value.clone()) {
+                Ok(converted) => converted,
+                Err(error) => {
+                    return Err(MediaError::InvalidValue {
+                        column: "DiskId",
+                        value: value.to_string(),
+                        reason: ::std::string::ToString::to_string(&error),
+                    });
+                }
+            }
+        },
+    };
+    assert!(output.contains(&disk_id.to_string()));
+    // Null values become `None` without being converted.
+    let cabinet = quote! {
+        let value = &row["Cabinet"];
+        if value.is_null() {
+            None
+        } else {
+            Some(match <String as ::core::convert::TryFrom<::msi::Value>>::try_from(value.clone()) {
+                Ok(converted) => converted,
+                Err(error) => {
+                    return Err(MediaError::InvalidValue {
+                        column: "Cabinet",
+                        value: value.to_string(),
+                        reason: ::std::string::ToString::to_string(&error),
+                    });
+                }
+            })
+        }
+    };
+    assert!(output.contains(&cabinet.to_string()));
+    // Errors name the table, the column and the value that failed.
+    let messages = quote! {
+        Self::MissingColumn { column } => {
+            write!(f, "Table `Media` has no column `{column}`", column = column)
+        }
+    };
+    assert!(output.contains(&messages.to_string()));
+    assert!(output.contains(
+        "\"Failed to convert value `{value}` in column `{column}` of table `Media`: {reason}\""
+    ));
+}
+
+#[test]
+fn test_msi_table_reports_every_error() {
+    let input = quote! {
+        #[msi_table(name = "Directory")]
+        struct Directory {
+            #[msi_column(primary_key, category = msi::Category::NotACategory, length = 72)]
+            directory: DirectoryIdentifier,
+            #[msi_column(category = msi::Category::DefaultDir)]
+            default_dir: DefaultDir,
+        }
+    };
+
+    let output = msi_tables::gen_tables_impl(input);
+
+    assert_eq!(
+        compile_errors(output),
+        vec![
+            "\"Category is invalid: NotACategory\"".to_string(),
+            "\"Field `default_dir` with category DefaultDir must define a length\"".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_msi_table_rejects_invalid_column_attributes() {
+    let input = quote! {
+        #[msi_table(name = "Registry")]
+        struct Registry {
+            #[msi_column(primary_key, identifier(generated), category = msi::Category::Text, length = 72)]
+            registry: Option<RegistryIdentifier>,
+            #[msi_column(localizable, category = msi::Category::Integer, length = 2)]
+            root: i16,
+            #[msi_column(category = msi::Category::Text, length = 256)]
+            value: String,
+        }
+    };
+
+    let output = msi_tables::gen_tables_impl(input);
+
+    assert_eq!(
+        compile_errors(output),
+        vec![
+            "\"Field `registry` is an identifier but has category Text. Identifiers must use the Identifier category\"".to_string(),
+            "\"Field `registry` is a primary key and cannot be nullable\"".to_string(),
+            "\"Field `root` with category Integer cannot be localizable\"".to_string(),
+            "\"Field `root` with category Integer cannot define a length. Integer columns are sized by their category\"".to_string(),
+            "\"Length of field `value` must be between 0 and 255\"".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_msi_table_enforces_structural_limits() {
+    let input = quote! {
+        enum MsiTables {
+            _Tables {
+                #[msi_column(primary_key, category = msi::Category::Text, length = 64)]
+                name: String,
+            },
+            ThisTableNameIsMuchTooLongForMsi {
+                #[msi_column(category = msi::Category::Text, length = 64)]
+                value: String,
+                #[msi_column(primary_key, category = msi::Category::Text, length = 64)]
+                name: String,
+                #[msi_column(category = msi::Category::Integer)]
+                a_column_name_that_is_far_too_long_to_be_stored_in_an_msi_database_at_all_by_a_wide_margin: i16,
+            }
+        }
+    };
+
+    let output = msi_tables::gen_tables_impl(input);
+
+    assert_eq!(
+        compile_errors(output),
+        vec![
+            "\"Table name `_Tables` is reserved by the MSI database\"".to_string(),
+            "\"Table name `ThisTableNameIsMuchTooLongForMsi` is longer than 31 characters\"".to_string(),
+            "\"Primary key column `Name` must come before every column that is not part of the primary key\"".to_string(),
+            "\"Column name `AColumnNameThatIsFarTooLongToBeStoredInAnMsiDatabaseAtAllByAWideMargin` is longer than 64 characters\"".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_msi_table_infers_category_from_field_type() {
+    let input = quote! {
+        #[msi_table(name = "Directory")]
+        struct Directory {
+            #[msi_column(primary_key, identifier(generated), length = 72)]
+            directory: DirectoryIdentifier,
+            #[msi_column(localizable, length = 255)]
+            default_dir: Option<DefaultDir>,
+            #[msi_column(category = msi::Category::Integer)]
+            display: i32,
+            attributes: Option<i32>,
+        }
+    };
+
+    let output = msi_tables::gen_tables_impl(input).to_string();
+
+    assert!(
+        output.contains(&quote! { .category(::msi::Category::Identifier).string(72) }.to_string())
+    );
+    assert!(
+        output.contains(&quote! { .category(::msi::Category::DefaultDir).string(255) }.to_string())
+    );
+    assert!(output.contains(&quote! { .category(msi::Category::Integer).int16() }.to_string()));
+    assert!(
+        output.contains(&quote! { .category(::msi::Category::DoubleInteger).int32() }.to_string())
+    );
+}
+
+#[test]
+fn test_msi_table_infers_length_from_category() {
+    let input = quote! {
+        #[msi_table(name = "Component")]
+        struct Component {
+            #[msi_column(primary_key, identifier(generated), length = 72)]
+            component: ComponentIdentifier,
+            #[msi_column(category = msi::Category::Guid)]
+            component_id: Option<String>,
+            #[msi_column(category = msi::Category::Guid, length = 40)]
+            key_path: Option<String>,
+        }
+    };
+
+    let output = msi_tables::gen_tables_impl(input).to_string();
+
+    assert!(output.contains(&quote! { .category(msi::Category::Guid).string(38) }.to_string()));
+    // An explicit length always takes precedence.
+    assert!(output.contains(&quote! { .category(msi::Category::Guid).string(40) }.to_string()));
+    assert!(output.contains(&quote! { let max_length = (38) as usize; }.to_string()));
+
+    // Identifier columns differ in length from table to table so they never get a default.
+    let input = quote! {
+        #[msi_table(name = "Feature")]
+        struct Feature {
+            #[msi_column(primary_key, identifier(generated))]
+            feature: FeatureIdentifier,
+        }
+    };
+
+    let output = msi_tables::gen_tables_impl(input);
+
+    assert_eq!(
+        compile_errors(output),
+        vec!["\"Field `feature` with category Identifier must define a length\"".to_string()]
+    );
+}
+
+#[test]
+fn test_msi_table_rejects_uninferable_category() {
+    let input = quote! {
+        #[msi_table(name = "Property")]
+        struct Property {
+            #[msi_column(primary_key, length = 72)]
+            property: String,
+        }
+    };
+
+    let output = msi_tables::gen_tables_impl(input);
+
+    assert_eq!(
+        compile_errors(output),
+        vec![
+            "\"Cannot infer the category of field `property` from its type. Add `category = msi::Category::...` to its `msi_column` attribute\"".to_string()
+        ]
+    );
+}
+
+#[test]
+fn test_msi_table_nullable_attributes() {
+    let input = quote! {
+        #[msi_table(name = "Property")]
+        struct Property {
+            #[msi_column(primary_key, identifier(generated), length = 72)]
+            property: PropertyIdentifier,
+            #[msi_column(nullable, category = msi::Category::Text, length = 0)]
+            value: MaybeText,
+        }
+    };
+
+    let output = msi_tables::gen_tables_impl(input).to_string();
+
+    assert!(output.contains(&quote! { ::msi::Column::build("Value").nullable() }.to_string()));
+}
+
+#[test]
+fn test_msi_table_nullable_alias_is_read_and_written_as_option() {
+    let input = quote! {
+        #[msi_table(name = "Note")]
+        struct Note {
+            #[msi_column(primary_key, category = msi::Category::Integer)]
+            id: i16,
+            #[msi_column(nullable, category = msi::Category::Text, length = 20)]
+            text: MaybeText,
+        }
+    };
+
+    let output = msi_tables::gen_tables_impl(input).to_string();
+
+    // The inner type of the alias cannot be named so it is inferred from the field.
+    let from_row = quote! {
+        if value.is_null() {
+            None
+        } else {
+            Some(match <_ as ::core::convert::TryFrom<::msi::Value>>::try_from(value.clone()) {
+                Ok(converted) => converted,
+                Err(error) => {
+                    return Err(NoteError::InvalidValue {
+                        column: "Text",
+                        value: value.to_string(),
+                        reason: ::std::string::ToString::to_string(&error),
+                    });
+                }
+            })
+        }
+    };
+    assert!(output.contains(&from_row.to_string()));
+    let to_row = quote! {
+        match &self.text {
+            Some(value) => ::msi::ToValue::to_value(value),
+            None => ::msi::Value::Null,
+        },
+    };
+    assert!(output.contains(&to_row.to_string()));
+}
+
+#[test]
+fn test_msi_table_nullable_alias_is_unique_and_indexed_by_its_values() {
+    let input = quote! {
+        #[msi_table(name = "Note")]
+        struct Note {
+            #[msi_column(primary_key, category = msi::Category::Integer)]
+            id: i16,
+            #[msi_column(nullable, unique, indexed, category = msi::Category::Text, length = 20)]
+            title: MaybeText,
+        }
+    };
+
+    let output = msi_tables::gen_tables_impl(input).to_string();
+
+    // Rows without a title never conflict.
+    let conflict = quote! {
+        if (self.title.is_some() && self.title == other.title) {
+            columns.extend(["Title"]);
+        }
+    };
+    assert!(output.contains(&conflict.to_string()));
+    let index = quote! {
+        index_by_title: ::core::option::Option<::std::collections::HashMap< <MaybeText as ::core::iter::IntoIterator>::Item, ::std::vec::Vec<usize>> >,
+    };
+    assert!(output.contains(&index.to_string()));
+    let find = quote! {
+        pub fn find_by_title<'a>(
+            &'a self,
+            value: &'a <MaybeText as ::core::iter::IntoIterator>::Item,
+        )
+    };
+    assert!(output.contains(&find.to_string()));
+    assert!(output.contains(&quote! { if let Some(value) = entry.title.as_ref() }.to_string()));
+}
+
+#[test]
+fn test_msi_table_rejects_conflicting_nullability() {
+    let input = quote! {
+        #[msi_table(name = "Property")]
+        struct Property {
+            #[msi_column(primary_key, nullable, identifier(generated), length = 72)]
+            property: PropertyIdentifier,
+            #[msi_column(not_null, category = msi::Category::Text, length = 0)]
+            value: Option<String>,
+            #[msi_column(nullable, not_null, category = msi::Category::Text, length = 0)]
+            comment: String,
+        }
+    };
+
+    let output = msi_tables::gen_tables_impl(input);
+
+    assert_eq!(
+        compile_errors(output),
+        vec![
+            "\"Field `property` is a primary key and cannot be nullable\"".to_string(),
+            "\"Field `value` is marked `not_null` but its type is an `Option`\"".to_string(),
+            "\"Field `comment` cannot be both `nullable` and `not_null`\"".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_msi_tables_enum_generates_validation_rows() {
+    let input = quote! {
+        enum MsiTables {
+            Directory {
+                #[msi_column(primary_key, identifier(generated), length = 72)]
+                directory: DirectoryIdentifier,
+                /// Reference to the entry in the Directory table that contains this directory.
+                #[msi_column(identifier(foreign_key = "Directory"), column_name = "Directory_Parent", length = 72)]
+                parent_directory: Option<DirectoryIdentifier>,
+            }
+        }
+    };
+
+    let output = msi_tables::gen_tables_impl(input).to_string();
+
+    let expected_row = quote! {
+        ::std::vec![
+            ::msi::Value::Str(::std::string::String::from("Directory")),
+            ::msi::Value::Str(::std::string::String::from("Directory_Parent")),
+            ::msi::Value::Str(::std::string::String::from("Y")),
+            ::msi::Value::Null,
+            ::msi::Value::Null,
+            ::msi::Value::Str(::std::string::String::from("Directory")),
+            ::msi::Value::Int(1i32),
+            ::msi::Value::Str(::std::string::String::from("Identifier")),
+            ::msi::Value::Null,
+            ::msi::Value::Str(::std::string::String::from(
+                "Reference to the entry in the Directory table that contains this directory."
+            )),
+        ]
+    };
+    assert!(output.contains(&expected_row.to_string()));
+    assert!(
+        output.contains(&quote! { rows.extend(DirectoryTable::validation_rows()); }.to_string())
+    );
+}
+
+#[test]
+fn test_msi_table_column_constraints() {
+    let input = quote! {
+        #[msi_table(name = "Registry")]
+        struct Registry {
+            #[msi_column(primary_key, identifier(generated), length = 72)]
+            registry: RegistryIdentifier,
+            #[msi_column(min = -1, max = 3)]
+            root: i16,
+            #[msi_column(set = ["a", "b"], category = msi::Category::Text, length = 10)]
+            kind: Option<String>,
+        }
+    };
+
+    let output = msi_tables::gen_tables_impl(input).to_string();
+
+    assert!(output.contains(&quote! { .range(-1i32, 3i32).int16() }.to_string()));
+    assert!(output.contains(&quote! { .enum_values(&["a", "b"]).string(10) }.to_string()));
+    assert!(output.contains(&quote! { if *number < -1i32 }.to_string()));
+    assert!(output.contains(&quote! { if *number > 3i32 }.to_string()));
+    assert!(
+        output.contains(
+            &quote! { ::msi::Value::Str(::std::string::String::from("a;b")) }.to_string()
+        )
+    );
+    // Only `try_new` checks the constraints, `new` trusts its caller.
+    let constructors = quote! {
+        pub fn new(
+            registry: impl ::core::convert::Into<RegistryIdentifier>,
+            root: impl ::core::convert::Into<i16>,
+            kind: impl ::core::convert::Into<Option<String> >
+        ) -> RegistryDao {
+            RegistryDao {
+                registry: registry.into(),
+                root: root.into(),
+                kind: kind.into(),
+            }
+        }
+    };
+    assert!(output.contains(&constructors.to_string()));
+    let try_new = quote! {
+        let dao = Self::new(registry, root, kind);
+        dao.check_constraints()?;
+        Ok(dao)
+    };
+    assert!(output.contains(&try_new.to_string()));
+}
+
+#[test]
+fn test_msi_table_rejects_invalid_column_constraints() {
+    let input = quote! {
+        #[msi_table(name = "Registry")]
+        struct Registry {
+            #[msi_column(primary_key, min = 0, length = 72)]
+            registry: RegistryIdentifier,
+            #[msi_column(min = 3, max = -1, set = ["a"])]
+            root: i16,
+            #[msi_column(max = "three")]
+            flags: i32,
+        }
+    };
+
+    let output = msi_tables::gen_tables_impl(input);
+
+    assert_eq!(
+        compile_errors(output),
+        vec![
+            "\"Field `registry` is not an integer column and cannot define `min` or `max`\""
+                .to_string(),
+            "\"`min` of field `root` is greater than its `max`\"".to_string(),
+            "\"`set` of field `root` must only contain integers\"".to_string(),
+            "\"Expected an integer literal\"".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_msi_table_try_new_checks_string_lengths() {
+    let input = quote! {
+        #[msi_table(name = "Property")]
+        struct Property {
+            #[msi_column(primary_key, identifier(generated), length = 72)]
+            property: PropertyIdentifier,
+            #[msi_column(category = msi::Category::Text, length = 0)]
+            value: String,
+        }
+    };
+
+    let output = msi_tables::gen_tables_impl(input).to_string();
+
+    let property_check = quote! {
+        let max_length = (72) as usize;
+        let length = text.chars().count();
+        if max_length != 0 && length > max_length {
+            return Err(PropertyError::TooLong {
+                field: "property",
+                column: "Property",
+                length,
+                max_length,
+            });
+        }
+    };
+    assert!(output.contains(&property_check.to_string()));
+    assert!(output.contains(&quote! { let max_length = (0) as usize; }.to_string()));
+    assert!(output.contains(&quote! { pub fn try_new }.to_string()));
+}
+
+#[test]
+fn test_msi_table_builder_defaults_nullable_fields() {
+    let input = quote! {
+        #[msi_table(name = "Shortcut")]
+        struct Shortcut {
+            #[msi_column(primary_key, identifier(generated), length = 72)]
+            shortcut: ShortcutIdentifier,
+            #[msi_column(category = msi::Category::Text, length = 255)]
+            arguments: Option<String>,
+        }
+    };
+
+    let output = msi_tables::gen_tables_impl(input).to_string();
+
+    let build = quote! {
+        let dao = ShortcutDao {
+            shortcut: self.shortcut.ok_or(ShortcutError::MissingField {
+                field: "shortcut",
+            })?,
+            arguments: self.arguments,
+        };
+    };
+    assert!(output.contains(&build.to_string()));
+    let setter = quote! {
+        pub fn arguments(mut self, arguments: impl ::core::convert::Into<String>) -> Self
+    };
+    assert!(output.contains(&setter.to_string()));
+}
+
+#[test]
+fn test_msi_table_builder_defaults_nullable_alias_fields() {
+    let input = quote! {
+        #[msi_table(name = "Note")]
+        struct Note {
+            #[msi_column(primary_key)]
+            id: i16,
+            #[msi_column(nullable, category = msi::Category::Text, length = 255)]
+            text: MaybeText,
+        }
+    };
+
+    let output = msi_tables::gen_tables_impl(input).to_string();
+
+    let stored = quote! {
+        pub struct NoteDaoBuilder {
+            id: ::core::option::Option<i16>,
+            text: MaybeText,
+        }
+    };
+    assert!(output.contains(&stored.to_string()));
+    let build = quote! {
+        let dao = NoteDao {
+            id: self.id.ok_or(NoteError::MissingField {
+                field: "id",
+            })?,
+            text: self.text,
+        };
+    };
+    assert!(output.contains(&build.to_string()));
+    let setter = quote! {
+        pub fn text(mut self, text: impl ::core::convert::Into<MaybeText>) -> Self {
+            self.text = text.into();
+            self
+        }
+    };
+    assert!(output.contains(&setter.to_string()));
+}
+
+#[test]
+fn test_msi_table_fills_default_and_generated_columns() {
+    let input = quote! {
+        #[msi_table(name = "File")]
+        struct File {
+            #[msi_column(primary_key, identifier(generated), length = 72)]
+            file: FileIdentifier,
+            #[msi_column(default = Some(0))]
+            attributes: Option<i16>,
+            #[msi_column(generated = next_sequence)]
+            sequence: i16,
+            #[msi_column(generated, category = msi::Category::Version, length = 72)]
+            version: String,
+        }
+    };
+
+    let output = msi_tables::gen_tables_impl(input).to_string();
+
+    let try_new = quote! {
+        pub fn try_new(file: impl ::core::convert::Into<FileIdentifier>) -> ::core::result::Result<FileDao, FileError>
+    };
+    assert!(output.contains(&try_new.to_string()));
+    let dao = quote! {
+        FileDao {
+            file: file.into(),
+            attributes: Some(0),
+            sequence: next_sequence(),
+            version: FileDao::generate_version(),
+        }
+    };
+    assert!(output.contains(&dao.to_string()));
+    let sequence_setter = quote! {
+        pub fn sequence(mut self, sequence: impl ::core::convert::Into<i16>) -> Self
+    };
+    assert!(!output.contains(&sequence_setter.to_string()));
+}
+
+#[test]
+fn test_msi_table_rejects_conflicting_fill_attributes() {
+    let input = quote! {
+        #[msi_table(name = "File")]
+        struct File {
+            #[msi_column(primary_key, identifier(generated), generated, length = 72)]
+            file: FileIdentifier,
+            #[msi_column(default = 1, generated)]
+            sequence: i16,
+        }
+    };
+
+    let output = msi_tables::gen_tables_impl(input);
+
+    assert_eq!(
+        compile_errors(output),
+        vec![
+            "\"Field `file` is created by the identifier generator and cannot have a `default` or be `generated`\"".to_string(),
+            "\"Field `sequence` cannot have both a `default` and be `generated`\"".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_msi_tables_enum_unique_columns_and_groups() {
+    let input = quote! {
+        enum MsiTables {
+            #[msi_table(unique(directory_, target))]
+            Shortcut {
+                #[msi_column(primary_key, identifier(generated), length = 72)]
+                shortcut: ShortcutIdentifier,
+                #[msi_column(identifier(foreign_key = "Shortcut"), length = 72)]
+                directory_: ShortcutIdentifier,
+                #[msi_column(unique, category = msi::Category::Text, length = 80)]
+                name: Option<String>,
+                #[msi_column(category = msi::Category::Text, length = 80)]
+                target: String,
+            }
+        }
+    };
+
+    let output = msi_tables::gen_tables_impl(input).to_string();
+
+    let checks = quote! {
+        let mut columns = ::std::vec::Vec::new();
+        if self.shortcut == other.shortcut {
+            columns.extend(["Shortcut"]);
+        }
+        if (self.name.is_some() && self.name == other.name) {
+            columns.extend(["Name"]);
+        }
+        if self.directory_ == other.directory_ && self.target == other.target {
+            columns.extend(["Directory_", "Target"]);
+        }
+        columns
+    };
+    assert!(output.contains(&checks.to_string()));
+}
+
+#[test]
+fn test_msi_table_rejects_unknown_unique_field() {
+    let input = quote! {
+        #[msi_table(name = "Shortcut", unique(shortcut, target))]
+        struct Shortcut {
+            #[msi_column(primary_key, identifier(generated), length = 72)]
+            shortcut: ShortcutIdentifier,
+        }
+    };
+
+    let output = msi_tables::gen_tables_impl(input);
+
+    assert_eq!(
+        compile_errors(output),
+        vec!["\"`unique` must only list fields of the table\"".to_string()]
+    );
+}
+
+#[test]
+fn test_msi_tables_enum_unique_scope_uses_shared_registry() {
+    let input = quote! {
+        enum MsiTables {
+            Directory {
+                #[msi_column(primary_key, identifier(generated), unique_scope = "Keys", length = 72)]
+                directory: DirectoryIdentifier,
+            },
+            Component {
+                #[msi_column(primary_key, identifier(generated), unique_scope = "Keys", length = 72)]
+                component: ComponentIdentifier,
+            }
         }
+    };
 
-        impl MsiTable for FeatureComponentTable {
-            type TableValue = FeatureComponentDao;
+    let output = msi_tables::gen_tables_impl(input).to_string();
 
-            fn name(&self) -> &'static str {
-                "FeatureComponent"
+    assert!(output.contains(&quote! { pub struct MsiTablesRegistry }.to_string()));
+    assert!(
+        output.contains(
+            &quote! {
+                registry: ::std::rc::Rc<::std::cell::RefCell<MsiTablesRegistry>>,
+                key_index: ::core::option::Option<::std::collections::HashMap<ComponentKey, usize>>,
+                entries: ::std::vec::Vec<ComponentDao>,
             }
+            .to_string()
+        )
+    );
+    assert!(
+        output.contains(
+            &quote! {
+                [("Keys", "Directory", ::msi::ToValue::to_value(&self.directory))]
+            }
+            .to_string()
+        )
+    );
+    assert!(output.contains(&quote! { registry.register(scope, value, "Component") }.to_string()));
+}
 
-            fn entries(&self) -> &Vec<FeatureComponentDao> {
-                &self.entries
+#[test]
+fn test_msi_table_rejects_unique_scope_outside_list() {
+    let input = quote! {
+        #[msi_table(name = "Directory")]
+        struct Directory {
+            #[msi_column(primary_key, identifier(generated), unique_scope = "Keys", length = 72)]
+            directory: DirectoryIdentifier,
+        }
+    };
+
+    let output = msi_tables::gen_tables_impl(input);
+
+    assert_eq!(
+        compile_errors(output),
+        vec![
+            "\"`unique_scope` is only supported for tables defined in `msi_table_list!`\""
+                .to_string()
+        ]
+    );
+}
+
+#[test]
+fn test_msi_table_calls_insert_and_remove_hooks() {
+    let input = quote! {
+        #[msi_table(name = "Property", on_insert = hooks::normalize, on_remove = hooks::keep)]
+        struct Property {
+            #[msi_column(primary_key, identifier(generated), length = 72)]
+            property: PropertyIdentifier,
+        }
+    };
+
+    let output = msi_tables::gen_tables_impl(input).to_string();
+
+    assert!(
+        output.contains(
+            &quote! {
+                let mut dao = dao;
+                hooks::normalize(self, &mut dao)?;
+            }
+            .to_string()
+        )
+    );
+    assert!(
+        output.contains(
+            &quote! {
+                if let Err(error) = hooks::keep(self, &dao) {
+                    self.entries.insert(index, dao);
+                    self.key_index = None;
+                    return Err(error);
+                }
             }
+            .to_string()
+        )
+    );
+}
 
-            fn entries_mut(&mut self) -> &mut Vec<FeatureComponentDao> {
-                &mut self.entries
+#[test]
+fn test_msi_table_upsert_and_remove_by_key_use_primary_key() {
+    let input = quote! {
+        #[msi_table(name = "FeatureComponents")]
+        struct FeatureComponents {
+            #[msi_column(primary_key, identifier(foreign_key = "Feature"), length = 38)]
+            feature_: FeatureIdentifier,
+            #[msi_column(primary_key, identifier(foreign_key = "Component"), length = 72)]
+            component_: ComponentIdentifier,
+        }
+    };
+
+    let output = msi_tables::gen_tables_impl(input).to_string();
+
+    assert!(
+        output.contains(
+            &quote! {
+                #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+                pub struct FeatureComponentsKey(pub FeatureIdentifier, pub ComponentIdentifier);
+            }
+            .to_string()
+        )
+    );
+    assert!(
+        output.contains(
+            &quote! {
+                pub fn key(&self) -> FeatureComponentsKey {
+                    FeatureComponentsKey(self.feature_.clone(), self.component_.clone())
+                }
+            }
+            .to_string()
+        )
+    );
+    assert!(
+        output.contains(
+            &quote! {
+                key_index: ::core::option::Option<::std::collections::HashMap<FeatureComponentsKey, usize>>,
             }
+            .to_string()
+        )
+    );
+    // Without other unique columns a conflict is only looked up through the key index.
+    assert!(output.contains(
+        &quote! {
+            pub fn find_conflict(&self, dao: &FeatureComponentsDao) -> ::core::option::Option<&FeatureComponentsDao> {
+                if let Some(position) = self.position_by_key(&dao.key()) {
+                    return Some(&self.entries[position]);
+                }
+                None
+            }
+        }
+        .to_string()
+    ));
+    assert!(
+        output.contains(
+            &quote! {
+                pub fn remove_by_key(
+                    &mut self,
+                    key: &FeatureComponentsKey,
+                ) -> ::core::result::Result<::core::option::Option<FeatureComponentsDao>, FeatureComponentsError>
+            }
+            .to_string()
+        )
+    );
+}
 
-            fn primary_key_indices(&self) -> Vec<usize> {
-                vec![0usize,1usize,]
+#[test]
+fn test_msi_table_looks_up_entries_by_key() {
+    let input = quote! {
+        #[msi_table(name = "FeatureComponents")]
+        struct FeatureComponents {
+            #[msi_column(primary_key, identifier(foreign_key = "Feature"), length = 38)]
+            feature_: FeatureIdentifier,
+            #[msi_column(primary_key, identifier(foreign_key = "Component"), length = 72)]
+            component_: ComponentIdentifier,
+        }
+    };
+
+    let output = msi_tables::gen_tables_impl(input).to_string();
+
+    assert!(
+        output.contains(
+            &quote! {
+                pub fn get(&self, key: &FeatureComponentsKey) -> ::core::option::Option<&FeatureComponentsDao> {
+                    self.position_by_key(key).map(|position| &self.entries[position])
+                }
+            }
+            .to_string()
+        )
+    );
+    assert!(output.contains(
+        &quote! { pub fn get_mut(&mut self, key: &FeatureComponentsKey) -> ::core::option::Option<&mut FeatureComponentsDao> }
+            .to_string()
+    ));
+    assert!(output.contains(
+        &quote! { pub fn contains_key(&self, key: &FeatureComponentsKey) -> bool }.to_string()
+    ));
+    assert!(
+        output.contains(
+            &quote! {
+                impl ::core::convert::From<(FeatureIdentifier, ComponentIdentifier)> for FeatureComponentsKey {
+                    fn from(value: (FeatureIdentifier, ComponentIdentifier)) -> Self {
+                        FeatureComponentsKey(value.0, value.1)
+                    }
+                }
             }
+            .to_string()
+        )
+    );
+    assert!(output.contains(
+        &quote! { impl<K: ::core::convert::Into<FeatureComponentsKey>> ::std::ops::Index<K> for FeatureComponentsTable }
+            .to_string()
+    ));
+}
+
+#[test]
+fn test_msi_table_finds_entries_by_foreign_key() {
+    let input = quote! {
+        #[msi_table(name = "Component")]
+        struct Component {
+            #[msi_column(primary_key, identifier(generated), length = 72)]
+            component: ComponentIdentifier,
+            #[msi_column(identifier(foreign_key = "Directory"), length = 72)]
+            directory_: DirectoryIdentifier,
+            #[msi_column(identifier(foreign_key = "Feature"), indexed, length = 38)]
+            feature_: Option<FeatureIdentifier>,
+        }
+    };
+
+    let output = msi_tables::gen_tables_impl(input).to_string();
 
-            fn primary_keys(&self) -> Vec<msi::ColumnType> {
-                vec![self.feature_.into(),self.component_.into(),]
+    assert!(
+        output.contains(
+            &quote! {
+                pub fn find_by_directory_<'a>(
+                    &'a self,
+                    value: &'a DirectoryIdentifier,
+                ) -> impl ::core::iter::Iterator<Item = &'a ComponentDao> + 'a {
+                    self.entries
+                        .iter()
+                        .filter(move |entry| Some(&entry.directory_) == Some(value))
+                }
+            }
+            .to_string()
+        )
+    );
+    assert!(
+        output.contains(
+            &quote! {
+                index_by_feature_: ::core::option::Option<::std::collections::HashMap<FeatureIdentifier, ::std::vec::Vec<usize>>>,
             }
+            .to_string()
+        )
+    );
+    assert!(output.contains(&quote! { let indexed = self.index_by_feature_.as_ref() }.to_string()));
+    assert!(!output.contains("index_by_directory_"));
+}
 
-            fn columns(&self) -> Vec<msi::Column> {
-                vec![
-                    msi::Column::build("Feature_").primary_key().foreign_key("Feature", 0).category(msi::Category::Identifier).string(72),
-                    msi::Column::build("Component_").primary_key().foreign_key("Component", 0).category(msi::Category::Identifier).string(72),
-                ]
+#[test]
+fn test_msi_table_rejects_field_named_key() {
+    let input = quote! {
+        #[msi_table(name = "Registry")]
+        struct Registry {
+            #[msi_column(primary_key, identifier(generated), length = 72)]
+            registry: RegistryIdentifier,
+            #[msi_column(category = msi::Category::RegPath, length = 255)]
+            key: String,
+        }
+    };
+
+    let output = msi_tables::gen_tables_impl(input);
+
+    assert_eq!(
+        compile_errors(output),
+        vec![
+            "\"Field `key` clashes with the generated `key()` accessor. Rename the field and set \
+            `column_name` instead\""
+                .to_string()
+        ]
+    );
+}
+
+#[test]
+fn test_msi_table_without_primary_key_has_no_key_methods() {
+    let input = quote! {
+        #[msi_table(name = "Notes")]
+        struct Notes {
+            #[msi_column(category = msi::Category::Text, length = 0)]
+            text: String,
+        }
+    };
+
+    let output = msi_tables::gen_tables_impl(input).to_string();
+
+    assert!(output.contains("try_insert"));
+    assert!(!output.contains("upsert"));
+    assert!(!output.contains("remove_by_key"));
+    assert!(!output.contains("NotesKey"));
+}
+
+#[test]
+fn test_msi_tables_enum_container_checks_references() {
+    let input = quote! {
+        enum MsiTables {
+            Directory {
+                #[msi_column(primary_key, identifier(generated), length = 72)]
+                directory: DirectoryIdentifier,
+            },
+            FeatureComponents {
+                #[msi_column(primary_key, identifier(foreign_key = "Directory"), length = 72)]
+                directory_: DirectoryIdentifier,
+                #[msi_column(primary_key, category = msi::Category::Integer)]
+                sequence: i16,
             }
         }
     };
 
-    // Compare the generated output with the expected output (e.g., using syn and comparing ASTs)
+    let output = msi_tables::gen_tables_impl(input).to_string();
+
+    assert!(
+        output.contains(
+            &quote! {
+                pub struct MsiTablesContainer {
+                    pub directory: DirectoryTable,
+                    pub feature_components: FeatureComponentsTable,
+                }
+            }
+            .to_string()
+        )
+    );
+    assert!(
+        output.contains(
+            &quote! {
+                Self {
+                    directory: DirectoryTable::new(used.clone()),
+                    feature_components: FeatureComponentsTable::default(),
+                }
+            }
+            .to_string()
+        )
+    );
+    assert!(
+        output.contains(
+            &quote! {
+                dangling.push(MsiTablesDanglingReference {
+                    table: MsiTablesKind::FeatureComponents,
+                    key: ::std::vec![
+                        ::msi::ToValue::to_value(&entry.directory_),
+                        ::msi::ToValue::to_value(&entry.sequence)
+                    ],
+                    column: "Directory_",
+                    value,
+                    referenced_table: MsiTablesKind::Directory,
+                });
+            }
+            .to_string()
+        )
+    );
+}
+
+#[test]
+fn test_msi_tables_enum_container_removes_cascade() {
+    let input = quote! {
+        enum MsiTables {
+            Feature {
+                #[msi_column(primary_key, identifier(generated), length = 38)]
+                feature: FeatureIdentifier,
+                #[msi_column(identifier(foreign_key = "Feature"), column_name = "Feature_Parent", length = 38)]
+                parent: Option<FeatureIdentifier>,
+            },
+            FeatureComponents {
+                #[msi_column(primary_key, identifier(foreign_key = "Feature"), length = 38)]
+                feature_: FeatureIdentifier,
+                #[msi_column(primary_key, category = msi::Category::Integer)]
+                sequence: i16,
+            }
+        }
+    };
+
+    let output = msi_tables::gen_tables_impl(input).to_string();
+
+    assert!(
+        output.contains(
+            &quote! {
+                pub fn remove_cascade(
+                    &mut self,
+                    table: MsiTablesKind,
+                    key: &[::msi::Value],
+                    cascade: MsiTablesCascade,
+                ) -> ::core::result::Result<::std::vec::Vec<MsiTablesDao>, MsiTablesError>
+            }
+            .to_string()
+        )
+    );
+    assert!(
+        output.contains(
+            &quote! {
+                if cascade == MsiTablesCascade::NullOut {
+                    let entries = ::whimsi_lib::tables::MsiTableKind::entries_mut(&mut self.feature);
+                    for entry in entries.iter_mut() {
+                        if entry.parent.as_ref().map(::msi::ToValue::to_value).as_ref() == Some(&value) {
+                            entry.parent = None;
+                        }
+                    }
+                }
+            }
+            .to_string()
+        )
+    );
+    // References that cannot be null are removed with the row they reference.
+    assert!(
+        output.contains(
+            &quote! {
+                if let Some(value) = Some(&dao.feature) {
+                    let value = ::msi::ToValue::to_value(value);
+                    for entry in &self.feature_components.entries {
+                        if Some(&entry.feature_).map(::msi::ToValue::to_value).as_ref() == Some(&value) {
+                            pending.push(MsiTablesCascadeRow::FeatureComponents(entry.key()));
+                        }
+                    }
+                }
+            }
+            .to_string()
+        )
+    );
+    assert!(output.contains(&quote! { MsiTablesDao::FeatureComponents(_) => {} }.to_string()));
+    // Rows that are waiting to be removed are found through the key index of their table.
+    assert!(
+        output.contains(
+            &quote! {
+                MsiTablesCascadeRow::FeatureComponents(key) => {
+                    let Some(position) = self.feature_components.position_by_key(&key) else {
+                        continue;
+                    };
+                    let dao = self
+                        .feature_components
+                        .remove(position)
+                        .map_err(MsiTablesError::FeatureComponents)?;
+                    MsiTablesDao::FeatureComponents(dao)
+                }
+            }
+            .to_string()
+        )
+    );
+}
+
+#[test]
+fn test_msi_tables_enum_resolves_foreign_key_index() {
+    let input = quote! {
+        enum MsiTables {
+            Shortcut {
+                #[msi_column(primary_key, identifier(foreign_key = "Directory"), category = msi::Category::Identifier, length = 72)]
+                directory_: DirectoryIdentifier,
+                #[msi_column(primary_key, identifier(generated), category = msi::Category::Identifier, length = 72)]
+                shortcut: ShortcutIdentifier,
+            },
+
+            Directory {
+                #[msi_column(primary_key, identifier(generated), category = msi::Category::Identifier, length = 72)]
+                directory: DirectoryIdentifier,
+                #[msi_column(identifier(foreign_key = "Shortcut"), category = msi::Category::Identifier, length = 72)]
+                shortcut_: Option<ShortcutIdentifier>,
+            }
+        }
+    };
+
+    let output = msi_tables::gen_tables_impl(input).to_string();
+
+    assert!(output.contains(&quote! { .foreign_key("Shortcut", 1) }.to_string()));
+    assert!(output.contains(&quote! { .foreign_key("Directory", 0) }.to_string()));
+}
+
+#[test]
+fn test_msi_tables_enum_rejects_unknown_foreign_key() {
+    let input = quote! {
+        enum MsiTables {
+            FeatureComponents {
+                #[msi_column(primary_key, identifier(foreign_key = "Feature"), category = msi::Category::Identifier, length = 72)]
+                feature_: FeatureIdentifier,
+                #[msi_column(primary_key, identifier(foreign_key = "Component"), category = msi::Category::Identifier, length = 72)]
+                component_: ComponentIdentifier,
+            }
+        }
+    };
+
+    let output = msi_tables::gen_tables_impl(input);
+
+    assert_eq!(
+        compile_errors(output),
+        vec![
+            "\"Foreign key references table `Feature` which is not defined in this list\""
+                .to_string(),
+            "\"Foreign key references table `Component` which is not defined in this list\""
+                .to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_msi_table_uses_overridden_crate_paths() {
+    let input = quote! {
+        #[msi_table(name = "Property", crate = "crate::whimsi", msi = "msi_renamed", macros = "crate::macros")]
+        struct Property {
+            #[msi_column(primary_key, identifier(generated), category = msi::Category::Identifier, length = 72)]
+            property: PropertyIdentifier,
+        }
+    };
+
+    let output = msi_tables::gen_tables_impl(input).to_string();
+
+    assert!(
+        output
+            .contains(&quote! { crate::whimsi::types::column::identifier::Identifier }.to_string())
+    );
+    assert!(output.contains(&quote! { msi_renamed::Column::build("Property") }.to_string()));
+    assert!(output.contains(&quote! { crate::macros::IdentifierToValue }.to_string()));
+    assert!(!output.contains("whimsi_lib"));
+    assert!(!output.contains(":: msi ::"));
+}
+
+#[test]
+fn test_msi_table_does_not_import_into_caller_namespace() {
+    let input = quote! {
+        #[msi_table(name = "Property")]
+        struct Property {
+            #[msi_column(primary_key, identifier(generated), category = msi::Category::Identifier, length = 72)]
+            property: PropertyIdentifier,
+        }
+    };
+
+    let output = msi_tables::gen_tables_impl(input);
+
     let parsed_output = syn::parse2::<syn::File>(output.clone())
         .unwrap_or_else(|_| panic!("Failed to parse output of test data:\n{}", output));
-    let parsed_expected =
-        syn::parse2::<syn::File>(expected_output).expect("Failed to parse reference test data");
+    assert!(
+        !parsed_output
+            .items
+            .iter()
+            .any(|item| matches!(item, syn::Item::Use(_)))
+    );
+}
+
+#[test]
+fn test_msi_tables_enum_does_not_rely_on_prelude_names() {
+    let input = quote! {
+        enum Installer {
+            Feature {
+                #[msi_column(primary_key, identifier(generated), unique_scope = "Keys", length = 38)]
+                feature: FeatureIdentifier,
+                #[msi_column(identifier(foreign_key = "Feature"), indexed, length = 38)]
+                parent: std::option::Option<FeatureIdentifier>,
+                #[msi_column(unique, category = msi::Category::Integer, min = 0)]
+                display: i16,
+            },
+        }
+    };
 
+    let output = msi_tables::gen_tables_impl(input);
+
+    // Each list gets its own kind so several lists can be defined in one module.
+    assert!(
+        output
+            .to_string()
+            .contains(&quote! { pub enum InstallerKind }.to_string())
+    );
+    let prelude_names = [
+        "Result",
+        "Option",
+        "Vec",
+        "String",
+        "TryFrom",
+        "From",
+        "Into",
+        "Default",
+        "Iterator",
+        "PartialEq",
+        "ToString",
+    ];
     assert_eq!(
-        parsed_output.to_token_stream().to_string(),
-        parsed_expected.to_token_stream().to_string()
+        unqualified_idents(output, &prelude_names),
+        Vec::<String>::new()
+    );
+}
+
+#[test]
+fn test_derive_msi_table_uses_struct_as_dao() {
+    let input = quote! {
+        #[derive(Clone, Debug, PartialEq, MsiTable)]
+        pub struct IconDao {
+            #[msi_column(primary_key, identifier(generated), category = msi::Category::Identifier, length = 72)]
+            pub name: IconIdentifier,
+        }
+    };
+
+    let output = msi_tables::gen_derive_impl(input);
+
+    let parsed_output = syn::parse2::<syn::File>(output.clone())
+        .unwrap_or_else(|_| panic!("Failed to parse output of test data:\n{}", output));
+    let structs = parsed_output
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            syn::Item::Struct(item) => Some(item.ident.to_string()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        structs,
+        vec![
+            "IconIdentifier",
+            "IconIdentifierGenerator",
+            "IconKey",
+            "IconDaoBuilder",
+            "IconTable"
+        ]
+    );
+    assert!(
+        output
+            .to_string()
+            .contains(&quote! { type TableValue = IconDao; }.to_string())
+    );
+}
+
+#[test]
+fn test_derive_msi_table_rejects_enums() {
+    let input = quote! {
+        #[derive(MsiTable)]
+        enum MsiTables {
+            Icon {
+                #[msi_column(primary_key, identifier(generated), category = msi::Category::Identifier, length = 72)]
+                name: IconIdentifier,
+            }
+        }
+    };
+
+    let output = msi_tables::gen_derive_impl(input);
+
+    assert_eq!(
+        compile_errors(output),
+        vec![
+            "\"`MsiTable` can only be derived for structs. Use `msi_table_list!` to define several tables at once.\""
+                .to_string()
+        ]
     );
 }
+
+/// Collects the messages of every `compile_error!` in the output of the macro.
+fn compile_errors(output: proc_macro2::TokenStream) -> Vec<String> {
+    let parsed_output = syn::parse2::<syn::File>(output.clone())
+        .unwrap_or_else(|_| panic!("Failed to parse output of test data:\n{}", output));
+    parsed_output
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            syn::Item::Macro(item)
+                if item.mac.path.segments.last().unwrap().ident == "compile_error" =>
+            {
+                Some(item.mac.tokens.to_string())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Every ident in `names` that is used without a path in front of it. Attributes such as derives
+/// are skipped.
+fn unqualified_idents(tokens: proc_macro2::TokenStream, names: &[&str]) -> Vec<String> {
+    let mut found = Vec::new();
+    let mut previous: Option<proc_macro2::TokenTree> = None;
+    for token in tokens {
+        match &token {
+            proc_macro2::TokenTree::Group(group) => {
+                let is_attribute = matches!(
+                    &previous,
+                    Some(proc_macro2::TokenTree::Punct(punct)) if punct.as_char() == '#'
+                );
+                if !is_attribute {
+                    found.extend(unqualified_idents(group.stream(), names));
+                }
+            }
+            proc_macro2::TokenTree::Ident(ident) if names.iter().any(|name| ident == name) => {
+                let is_qualified = matches!(
+                    &previous,
+                    Some(proc_macro2::TokenTree::Punct(punct)) if punct.as_char() == ':'
+                );
+                if !is_qualified {
+                    found.push(ident.to_string());
+                }
+            }
+            _ => {}
+        }
+        previous = Some(token);
+    }
+    found
+}
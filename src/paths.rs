@@ -0,0 +1,58 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Paths to the crates that the generated code depends on. Each can be overridden with
+/// `#[msi_table(crate = "...", msi = "...", macros = "...")]` so the macro also works from inside
+/// whimsi-lib itself, from crates that re-export it, or when `msi` is renamed in `Cargo.toml`.
+pub(crate) struct CratePaths {
+    // Path to whimsi-lib.
+    pub krate: syn::Path,
+    // Path to the msi crate.
+    pub msi: syn::Path,
+    // Path to the whimsi-macros crate that provides `IdentifierToValue`.
+    pub macros: syn::Path,
+}
+
+impl CratePaths {
+    pub fn new(
+        krate: Option<syn::Path>,
+        msi: Option<syn::Path>,
+        macros: Option<syn::Path>,
+    ) -> CratePaths {
+        CratePaths {
+            krate: krate.unwrap_or_else(|| syn::parse_quote!(::whimsi_lib)),
+            msi: msi.unwrap_or_else(|| syn::parse_quote!(::msi)),
+            macros: macros.unwrap_or_else(|| syn::parse_quote!(::whimsi_macros)),
+        }
+    }
+
+    pub fn identifier(&self) -> TokenStream {
+        let krate = &self.krate;
+        quote! { #krate::types::column::identifier::Identifier }
+    }
+
+    pub fn to_identifier(&self) -> TokenStream {
+        let krate = &self.krate;
+        quote! { #krate::types::column::identifier::ToIdentifier }
+    }
+
+    pub fn identifier_generator(&self) -> TokenStream {
+        let krate = &self.krate;
+        quote! { #krate::types::helpers::id_generator::IdentifierGenerator }
+    }
+
+    pub fn msi_dao(&self) -> TokenStream {
+        let krate = &self.krate;
+        quote! { #krate::tables::MsiDao }
+    }
+
+    pub fn primary_identifier(&self) -> TokenStream {
+        let krate = &self.krate;
+        quote! { #krate::tables::PrimaryIdentifier }
+    }
+
+    pub fn msi_table_kind(&self) -> TokenStream {
+        let krate = &self.krate;
+        quote! { #krate::tables::MsiTableKind }
+    }
+}
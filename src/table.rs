@@ -1,31 +1,590 @@
 use proc_macro2::TokenStream;
 use quote::quote;
-use std::str::FromStr;
+use syn::Ident;
 
-use crate::{helper::*, msi_tables::FieldInformation};
+use crate::{
+    constraint::ColumnConstraints,
+    helper::*,
+    lookup::{generate_index_fields, generate_lookup_tokens, index_ident, indexed_fields},
+    msi_tables::{FieldInformation, TableList, TableOptions},
+    paths::CratePaths,
+};
 
-pub fn generate_table_tokens(target_name: &str, fields: &[FieldInformation]) -> TokenStream {
-    let table_definition_tokens = generate_table_definition(target_name);
-    let msi_table_impl_tokens = generate_msi_table_impl(target_name, fields);
-    quote! {
+pub fn generate_table_tokens(
+    target_name: &str,
+    dao_name: &Ident,
+    fields: &[FieldInformation],
+    options: &TableOptions,
+    tables: &TableList,
+    paths: &CratePaths,
+) -> darling::Result<TokenStream> {
+    let table_definition_tokens =
+        generate_table_definition(target_name, dao_name, fields, tables, paths);
+    let insert_tokens =
+        generate_insert_for_table(target_name, dao_name, fields, options, tables, paths);
+    let msi_table_impl_tokens =
+        generate_msi_table_impl(target_name, dao_name, fields, tables, paths)?;
+    Ok(quote! {
         #table_definition_tokens
+        #insert_tokens
         #msi_table_impl_tokens
-    }
+    })
 }
 
-fn generate_table_definition(target_name: &str) -> TokenStream {
+fn generate_table_definition(
+    target_name: &str,
+    dao_type: &Ident,
+    fields: &[FieldInformation],
+    tables: &TableList,
+    paths: &CratePaths,
+) -> TokenStream {
     let table_ident = table_from_name(target_name);
-    let dao_type = dao_from_name(target_name);
+    let registry = scope_registry(fields, tables);
+    let has_key = fields.iter().any(|field| field.primary_key);
+    let indexed = indexed_fields(fields);
+
+    let mut field_tokens = TokenStream::new();
+    let mut method_tokens = TokenStream::new();
+    // Every field except the indexes, which only cache the positions of `entries`.
+    let mut compared_fields = vec![quote! { entries }];
+    if fields.iter().any(FieldInformation::is_generated_identifier) {
+        let generator_type = identifier_generator_from_name(target_name);
+        let identifier = paths.identifier();
+        let new_comment = &format!(
+            "Creates an empty `{table_ident}` whose generator never creates an identifier that is \
+            already in `used`. The identifiers of rows added to the table are recorded in `used` as \
+            well. Share `used` between tables so identifiers are unique across them."
+        );
+        let registry_field =
+            registry.map(|_| quote! { registry: ::core::default::Default::default(), });
+        let key_index_field = has_key.then(|| quote! { key_index: None, });
+        let index_fields = indexed.iter().map(|field| index_ident(field));
+        field_tokens.extend(quote! { generator: #generator_type, });
+        compared_fields.push(quote! { generator });
+        method_tokens.extend(quote! {
+            #[doc = #new_comment]
+            pub fn new(used: ::std::rc::Rc<::std::cell::RefCell<::std::vec::Vec<#identifier>>>) -> #table_ident {
+                #table_ident {
+                    generator: used.into(),
+                    #registry_field
+                    #key_index_field
+                    #(#index_fields: None,)*
+                    entries: ::std::vec::Vec::new(),
+                }
+            }
+
+            /// The generator used to create identifiers for new rows in this table.
+            pub fn generator_mut(&mut self) -> &mut #generator_type {
+                &mut self.generator
+            }
+        });
+    }
+    if let Some(registry) = registry {
+        field_tokens.extend(quote! {
+            registry: ::std::rc::Rc<::std::cell::RefCell<#registry>>,
+        });
+        compared_fields.push(quote! { registry });
+        method_tokens.extend(quote! {
+            /// Checks the `unique_scope` columns of new rows against `registry`. Share the same
+            /// registry between tables before adding rows to them.
+            pub fn with_registry(
+                mut self,
+                registry: ::std::rc::Rc<::std::cell::RefCell<#registry>>,
+            ) -> #table_ident {
+                self.registry = registry;
+                self
+            }
+        });
+    }
+
+    let impl_tokens = if method_tokens.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            impl #table_ident {
+                #method_tokens
+            }
+        }
+    };
+    if !has_key && indexed.is_empty() {
+        return quote! {
+            #[derive(Clone, Debug, Default, PartialEq)]
+            pub struct #table_ident {
+                #field_tokens
+                entries: ::std::vec::Vec<#dao_type>,
+            }
+
+            #impl_tokens
+        };
+    }
 
+    let key_index_field = has_key.then(|| {
+        let key_ident = key_from_name(target_name);
+        quote! {
+            // Position of every entry by its primary key. `None` until it is needed again after
+            // the entries were changed directly.
+            key_index: ::core::option::Option<::std::collections::HashMap<#key_ident, usize>>,
+        }
+    });
+    let index_fields = generate_index_fields(fields);
     quote! {
-        #[derive(Clone, Debug, PartialEq)]
+        #[derive(Clone, Debug, Default)]
         pub struct #table_ident {
-            entries: Vec<#dao_type>,
+            #field_tokens
+            #key_index_field
+            #index_fields
+            entries: ::std::vec::Vec<#dao_type>,
         }
+
+        impl ::core::cmp::PartialEq for #table_ident {
+            fn eq(&self, other: &Self) -> bool {
+                #(self.#compared_fields == other.#compared_fields)&&*
+            }
+        }
+
+        #impl_tokens
     }
 }
 
-fn generate_msi_table_impl(target_name: &str, fields: &[FieldInformation]) -> TokenStream {
+/// The registry that the table checks its `unique_scope` columns against, if it has any.
+fn scope_registry<'a>(fields: &[FieldInformation], tables: &'a TableList) -> Option<&'a Ident> {
+    tables
+        .registry()
+        .filter(|_| fields.iter().any(|field| field.unique_scope.is_some()))
+}
+
+fn generate_insert_for_table(
+    target_name: &str,
+    dao_name: &Ident,
+    fields: &[FieldInformation],
+    options: &TableOptions,
+    tables: &TableList,
+    paths: &CratePaths,
+) -> TokenStream {
+    let table_ident = table_from_name(target_name);
+    let error_name = error_from_name(target_name);
+    let msi_dao = paths.msi_dao();
+    let has_key = fields.iter().any(|field| field.primary_key);
+    let scoped = scope_registry(fields, tables).is_some();
+    let (register, unregister, scope_tokens) = if scoped {
+        (
+            quote! { self.register_scoped_values(&dao, None)?; },
+            quote! { self.unregister_scoped_values(&dao); },
+            generate_scope_registration(target_name, dao_name),
+        )
+    } else {
+        Default::default()
+    };
+    let (claim_identifier, release_identifier, identifier_tokens) =
+        match fields.iter().find(|field| field.is_generated_identifier()) {
+            Some(field) => (
+                quote! { self.claim_identifier(&dao); },
+                quote! { self.release_identifier(&dao); },
+                generate_identifier_registration(dao_name, field, paths),
+            ),
+            None => Default::default(),
+        };
+    let on_insert = options.on_insert.as_ref().map(|on_insert| {
+        quote! {
+            let mut dao = dao;
+            #on_insert(self, &mut dao)?;
+        }
+    });
+    let is_indexed = !indexed_fields(fields).is_empty();
+    let (find_by_key, index_insert, index_remove, key_index_reset) = if has_key {
+        (
+            quote! {
+                if let Some(position) = self.position_by_key(&dao.key()) {
+                    return Some(&self.entries[position]);
+                }
+            },
+            quote! {
+                let position = self.entries.len();
+                self.key_index_mut().insert(dao.key(), position);
+            },
+            quote! {
+                if let Some(key_index) = &mut self.key_index {
+                    key_index.remove(&dao.key());
+                    for position in key_index.values_mut() {
+                        if *position > index {
+                            *position -= 1;
+                        }
+                    }
+                }
+            },
+            quote! { self.key_index = None; },
+        )
+    } else {
+        Default::default()
+    };
+    let (index_pushed, remove_from_indexes, drop_indexes) = if is_indexed {
+        (
+            quote! { self.index_entry(self.entries.len() - 1); },
+            quote! { self.remove_from_indexes(index); },
+            quote! { self.drop_indexes(); },
+        )
+    } else {
+        Default::default()
+    };
+    // Only the primary key is looked up through the index. Other unique values still need every
+    // entry to be compared.
+    let find_unique = if !has_key || has_unique_values(fields, options) {
+        quote! {
+            self.entries
+                .iter()
+                .find(|entry| #msi_dao::conflicts_with(*entry, dao))
+        }
+    } else {
+        quote! { None }
+    };
+    // The row is taken out before the hook is called so the hook can borrow the table mutably.
+    let on_remove = options.on_remove.as_ref().map(|on_remove| {
+        quote! {
+            if let Err(error) = #on_remove(self, &dao) {
+                self.entries.insert(index, dao);
+                #key_index_reset
+                #drop_indexes
+                return Err(error);
+            }
+        }
+    });
+    let index_tokens = if has_key {
+        let key_ident = key_from_name(target_name);
+        quote! {
+            impl<K: ::core::convert::Into<#key_ident>> ::std::ops::Index<K> for #table_ident {
+                type Output = #dao_name;
+
+                /// The entry with the given primary key.
+                ///
+                /// # Panics
+                ///
+                /// Panics if no entry has the key. Use `get` to handle this instead.
+                fn index(&self, key: K) -> &#dao_name {
+                    let key = key.into();
+                    self.get(&key)
+                        .unwrap_or_else(|| panic!("No entry with key {key:?}"))
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+    let key_tokens = generate_key_methods_for_table(
+        target_name,
+        dao_name,
+        fields,
+        options,
+        &on_insert,
+        scoped,
+        paths,
+    );
+    let lookup_tokens = generate_lookup_tokens(dao_name, fields);
+    quote! {
+        impl #table_ident {
+            /// The entry that `dao` cannot be stored next to because they share a primary key or a
+            /// unique value. The primary key is looked up through the key index, `unique` columns
+            /// and groups are compared with every entry.
+            pub fn find_conflict(&self, dao: &#dao_name) -> ::core::option::Option<&#dao_name> {
+                #find_by_key
+                #find_unique
+            }
+
+            /// Adds the row for callers that know it does not conflict with the table.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the row is refused. Use `try_insert` to handle this instead.
+            pub fn insert(&mut self, dao: #dao_name) {
+                self.try_insert(dao).unwrap_or_else(|error| panic!("{error}"))
+            }
+
+            /// Adds the row unless it conflicts with a row that is already in the table.
+            pub fn try_insert(&mut self, dao: #dao_name) -> ::core::result::Result<(), #error_name> {
+                #on_insert
+                if let Some(entry) = self.find_conflict(&dao) {
+                    return Err(#error_name::Conflict {
+                        columns: entry.conflicting_columns(&dao),
+                    });
+                }
+                #register
+                #claim_identifier
+                #index_insert
+                self.entries.push(dao);
+                #index_pushed
+                Ok(())
+            }
+
+            /// Removes and returns the entry at `index`.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `index` is out of bounds.
+            pub fn remove(&mut self, index: usize) -> ::core::result::Result<#dao_name, #error_name> {
+                let dao = self.entries.remove(index);
+                #index_remove
+                #remove_from_indexes
+                #on_remove
+                #unregister
+                #release_identifier
+                Ok(dao)
+            }
+
+            #key_tokens
+            #lookup_tokens
+            #scope_tokens
+            #identifier_tokens
+        }
+
+        #index_tokens
+    }
+}
+
+/// Whether the table has `unique` columns or groups next to its primary key.
+fn has_unique_values(fields: &[FieldInformation], options: &TableOptions) -> bool {
+    !options.unique.is_empty() || fields.iter().any(|field| field.unique.is_present())
+}
+
+/// Methods that find rows by their primary key. Tables without a primary key have none.
+fn generate_key_methods_for_table(
+    target_name: &str,
+    dao_name: &Ident,
+    fields: &[FieldInformation],
+    options: &TableOptions,
+    on_insert: &Option<TokenStream>,
+    scoped: bool,
+    paths: &CratePaths,
+) -> TokenStream {
+    if !fields.iter().any(|field| field.primary_key) {
+        return quote! {};
+    }
+    let error_name = error_from_name(target_name);
+    let key_ident = key_from_name(target_name);
+    let msi_dao = paths.msi_dao();
+    let (replace, index_pushed, drop_indexes) = if indexed_fields(fields).is_empty() {
+        (
+            quote! { Some(::std::mem::replace(&mut self.entries[index], dao)) },
+            quote! {},
+            quote! {},
+        )
+    } else {
+        (
+            quote! {
+                {
+                    self.unindex_entry(index);
+                    let previous = ::std::mem::replace(&mut self.entries[index], dao);
+                    self.index_entry(index);
+                    Some(previous)
+                }
+            },
+            quote! { self.index_entry(position); },
+            // Any indexed value may be changed through the returned reference.
+            quote! { self.drop_indexes(); },
+        )
+    };
+    let claim_identifier = fields
+        .iter()
+        .any(FieldInformation::is_generated_identifier)
+        .then(|| quote! { self.claim_identifier(&dao); });
+    let register = if scoped {
+        quote! {
+            self.register_scoped_values(&dao, index.map(|index| &self.entries[index]))?;
+        }
+    } else {
+        quote! {}
+    };
+    // Another entry can only share a unique value with the row as the primary key is looked up
+    // through the index.
+    let find_other_conflict = if has_unique_values(fields, options) {
+        quote! {
+            if let Some((_, entry)) = self
+                .entries
+                .iter()
+                .enumerate()
+                .find(|(position, entry)| {
+                    Some(*position) != index && #msi_dao::conflicts_with(*entry, &dao)
+                })
+            {
+                return Err(#error_name::Conflict {
+                    columns: entry.conflicting_columns(&dao),
+                });
+            }
+        }
+    } else {
+        quote! {}
+    };
+    quote! {
+        /// The position of the entry with the given primary key.
+        fn position_by_key(&self, key: &#key_ident) -> ::core::option::Option<usize> {
+            match &self.key_index {
+                Some(key_index) => key_index.get(key).copied(),
+                None => self.entries.iter().position(|entry| entry.key() == *key),
+            }
+        }
+
+        /// The position of every entry by its primary key. Rebuilt from the entries if it was
+        /// dropped.
+        fn key_index_mut(&mut self) -> &mut ::std::collections::HashMap<#key_ident, usize> {
+            let entries = &self.entries;
+            self.key_index.get_or_insert_with(|| {
+                entries
+                    .iter()
+                    .enumerate()
+                    .map(|(position, entry)| (entry.key(), position))
+                    .collect()
+            })
+        }
+
+        /// Adds the row or replaces the entry with the same primary key. Returns the replaced
+        /// entry. Fails if the row conflicts with any other entry. Like `find_conflict` this
+        /// compares the `unique` values of the row with every entry.
+        pub fn upsert(&mut self, dao: #dao_name) -> ::core::result::Result<::core::option::Option<#dao_name>, #error_name> {
+            #on_insert
+            let key = dao.key();
+            let index = self.position_by_key(&key);
+            #find_other_conflict
+            #register
+            Ok(match index {
+                Some(index) => #replace,
+                None => {
+                    #claim_identifier
+                    let position = self.entries.len();
+                    self.key_index_mut().insert(key, position);
+                    self.entries.push(dao);
+                    #index_pushed
+                    None
+                }
+            })
+        }
+
+        /// The entry with the given primary key.
+        pub fn get(&self, key: &#key_ident) -> ::core::option::Option<&#dao_name> {
+            self.position_by_key(key).map(|position| &self.entries[position])
+        }
+
+        /// The entry with the given primary key. The primary key of the entry must not be changed
+        /// through the reference as the table would no longer find it by its key.
+        pub fn get_mut(&mut self, key: &#key_ident) -> ::core::option::Option<&mut #dao_name> {
+            #drop_indexes
+            self.position_by_key(key)
+                .map(|position| &mut self.entries[position])
+        }
+
+        /// Whether an entry has the given primary key.
+        pub fn contains_key(&self, key: &#key_ident) -> bool {
+            self.position_by_key(key).is_some()
+        }
+
+        /// The primary key of every entry in the order of the entries.
+        pub fn primary_keys(&self) -> impl ::core::iter::Iterator<Item = #key_ident> + '_ {
+            self.entries.iter().map(#dao_name::key)
+        }
+
+        /// Removes and returns the entry with the given primary key, if there is one.
+        pub fn remove_by_key(
+            &mut self,
+            key: &#key_ident,
+        ) -> ::core::result::Result<::core::option::Option<#dao_name>, #error_name> {
+            match self.position_by_key(key) {
+                Some(index) => self.remove(index).map(Some),
+                None => Ok(None),
+            }
+        }
+    }
+}
+
+/// Keeps the registry shared with other tables in sync with the `unique_scope` columns of the
+/// entries.
+fn generate_scope_registration(target_name: &str, dao_name: &Ident) -> TokenStream {
+    let error_name = error_from_name(target_name);
+    // Every value is checked before any is registered so a refused row leaves the registry
+    // untouched.
+    quote! {
+        /// Claims the `unique_scope` values of `dao` in the registry. Values of `previous`, the
+        /// entry that `dao` replaces, are released.
+        fn register_scoped_values(
+            &self,
+            dao: &#dao_name,
+            previous: ::core::option::Option<&#dao_name>,
+        ) -> ::core::result::Result<(), #error_name> {
+            let values = dao.scoped_values();
+            let previous = previous.map(#dao_name::scoped_values).unwrap_or_default();
+            let mut registry = self.registry.borrow_mut();
+            for (scope, column, value) in &values {
+                let replaced = previous
+                    .iter()
+                    .any(|(previous_scope, _, previous_value)| {
+                        previous_scope == scope && previous_value == value
+                    });
+                if replaced {
+                    continue;
+                }
+                if let Some(table) = registry.owner(scope, value) {
+                    return Err(#error_name::ScopeConflict {
+                        column,
+                        scope,
+                        value: value.to_string(),
+                        table,
+                    });
+                }
+            }
+            for (scope, _, value) in previous {
+                registry.unregister(scope, &value);
+            }
+            for (scope, _, value) in values {
+                let _ = registry.register(scope, value, #target_name);
+            }
+            Ok(())
+        }
+
+        /// Releases the `unique_scope` values of `dao` in the registry.
+        fn unregister_scoped_values(&self, dao: &#dao_name) {
+            let mut registry = self.registry.borrow_mut();
+            for (scope, _, value) in dao.scoped_values() {
+                registry.unregister(scope, &value);
+            }
+        }
+    }
+}
+
+/// Keeps the identifiers used by the entries in the `used` list of the generator so it never creates
+/// an identifier that a row added by hand already has.
+fn generate_identifier_registration(
+    dao_name: &Ident,
+    field: &FieldInformation,
+    paths: &CratePaths,
+) -> TokenStream {
+    let field_ident = field.ident();
+    let to_identifier = paths.to_identifier();
+    let identifier_generator = paths.identifier_generator();
+    quote! {
+        /// Records the identifier of `dao` as used so the generator does not create it again.
+        fn claim_identifier(&self, dao: &#dao_name) {
+            let identifier = #to_identifier::to_identifier(&dao.#field_ident);
+            let mut used = #identifier_generator::used(&self.generator).borrow_mut();
+            if !used.contains(&identifier) {
+                used.push(identifier);
+            }
+        }
+
+        /// Frees the identifier of `dao` so the generator may create it again.
+        fn release_identifier(&self, dao: &#dao_name) {
+            let identifier = #to_identifier::to_identifier(&dao.#field_ident);
+            #identifier_generator::used(&self.generator)
+                .borrow_mut()
+                .retain(|used| *used != identifier);
+        }
+    }
+}
+
+fn generate_msi_table_impl(
+    target_name: &str,
+    dao_name: &Ident,
+    fields: &[FieldInformation],
+    tables: &TableList,
+    paths: &CratePaths,
+) -> darling::Result<TokenStream> {
+    let msi = &paths.msi;
+    let msi_table_kind = paths.msi_table_kind();
     let primary_key_indices = fields
         .iter()
         .enumerate()
@@ -37,111 +596,146 @@ fn generate_msi_table_impl(target_name: &str, fields: &[FieldInformation]) -> To
             }
         });
 
+    // Keep going after a bad column so every invalid column is reported in one pass.
+    let mut errors = darling::Error::accumulator();
     let columns = fields.iter().fold(quote! {}, |acc, field| {
-        let field_ident = &field.ident.clone().expect("Field doesn't have an identifier");
-
-        let column_name = if let Some(column_name) = &field.column_name {column_name} else { &snake_case_to_pascal_case(&field_ident.to_string())};
-        let nullable = if let syn::Type::Path(path) = &field.ty &&
-                 path.path.segments.last().unwrap().ident == "Option" {
-                    quote!{.nullable()}
-                }
-            else {
-                Default::default()
-            };
-
-        let primary_key = if field.primary_key {
-            quote!{.primary_key()}
-        } else {
-            Default::default()
-        };
-        let localizable = if field.localizable {
-            quote!{.localizable()}
-        } else {
-            Default::default()
-        };
-
-        // If this causes issues it can probably be removed.
-        let foreign_key = if let Some(identifier_options) = &field.identifier_options &&
-            let Some(foreign_key) = &identifier_options.foreign_key {
-            // TODO: This is almost certainly wrong in some circumstance. It assumes that the
-            // foreign_key points to the first column of the referenced table. I really want to add
-            // a way to dynamically get the primary_key index for the given table, but I would need
-            // to split the parsing into 2 sections for that. I might circle back and implement
-            // that at some point but I'm gonna skip it for now.
-            quote!{.foreign_key(#foreign_key, 0)}
-        } else {
-            Default::default()
-        };
-
-        // TODO: I dislike having to hard code in the `msi` path here but couldn't find a
-        // better solution. Should probably look into it some more.
-        let field_category = &field.category;
-        let category = quote! { .category( #field_category ) };
-        let finish = generate_finish_build_for_field(field);
-
+        let column = errors.handle(generate_column_definition(field, tables, paths));
         quote! {
             #acc
-
-            msi::Column::build(#column_name) #primary_key #nullable #localizable #foreign_key #category #finish,
+            #column
         }
     });
 
     let table_name = table_from_name(target_name);
-    let dao_name = dao_from_name(target_name);
+    // The entries may be changed in any way so the indexes are rebuilt when they are next needed.
+    let mut drop_indexes = if fields.iter().any(|field| field.primary_key) {
+        quote! { self.key_index = None; }
+    } else {
+        quote! {}
+    };
+    if !indexed_fields(fields).is_empty() {
+        drop_indexes.extend(quote! { self.drop_indexes(); });
+    }
 
-    quote! {
-        impl MsiTableKind for #table_name {
+    errors.finish_with(quote! {
+        impl #msi_table_kind for #table_name {
             type TableValue = #dao_name;
 
             fn name(&self) -> &'static str {
                 #target_name
             }
 
-            fn entries(&self) -> &Vec<#dao_name> {
+            fn entries(&self) -> &::std::vec::Vec<#dao_name> {
                 &self.entries
             }
 
-            fn entries_mut(&mut self) -> &mut Vec<#dao_name> {
+            fn entries_mut(&mut self) -> &mut ::std::vec::Vec<#dao_name> {
+                #drop_indexes
                 &mut self.entries
             }
 
-            fn primary_key_indices(&self) -> Vec<usize> {
-                vec![#primary_key_indices]
+            fn primary_key_indices(&self) -> ::std::vec::Vec<usize> {
+                ::std::vec![#primary_key_indices]
             }
 
-            fn columns(&self) -> Vec<msi::Column> {
-                vec![
+            fn columns(&self) -> ::std::vec::Vec<#msi::Column> {
+                ::std::vec![
                     #columns
                 ]
             }
         }
-    }
+    })
 }
 
-fn generate_finish_build_for_field(field: &FieldInformation) -> TokenStream {
-    let syn::Expr::Path(ref path) = field.category else {
-        panic!("Category is not a valid syn::Expr::Path.")
+fn generate_column_definition(
+    field: &FieldInformation,
+    tables: &TableList,
+    paths: &CratePaths,
+) -> darling::Result<TokenStream> {
+    let msi = &paths.msi;
+    let column_name = field.column_name();
+    let nullable = if field.is_nullable() {
+        quote! {.nullable()}
+    } else {
+        Default::default()
+    };
+
+    let primary_key = if field.primary_key {
+        quote! {.primary_key()}
+    } else {
+        Default::default()
+    };
+    let localizable = if field.localizable.is_present() {
+        quote! {.localizable()}
+    } else {
+        Default::default()
+    };
+
+    let foreign_key = if let Some(foreign_key) = field.foreign_key() {
+        let foreign_key_index =
+            proc_macro2::Literal::usize_unsuffixed(tables.foreign_key_index(foreign_key)?);
+        quote! {.foreign_key(#foreign_key, #foreign_key_index)}
+    } else {
+        Default::default()
+    };
+
+    let category = match &field.category {
+        Some(category) => quote! { .category( #category ) },
+        None => {
+            let variant = field.category_variant()?;
+            quote! { .category( #msi::Category::#variant ) }
+        }
     };
-    let category_str = path
-        .path
-        .segments
-        .last()
-        .expect("Path contains no segments")
-        .ident
-        .to_string();
-    let category = msi::Category::from_str(&category_str)
-        .unwrap_or_else(|_| panic!("Category is invalid: {}", category_str));
+    let msi_category = field.msi_category()?;
+    field.validate(msi_category)?;
+    let constraints =
+        generate_constraints_for_field(&ColumnConstraints::parse(field)?, msi_category);
+    let finish = generate_finish_build_for_field(field, msi_category)?;
+
+    Ok(quote! {
+        #msi::Column::build(#column_name) #primary_key #nullable #localizable #foreign_key #category #constraints #finish,
+    })
+}
+
+fn generate_constraints_for_field(
+    constraints: &ColumnConstraints,
+    category: msi::Category,
+) -> TokenStream {
+    let mut tokens = TokenStream::new();
+    if constraints.min.is_some() || constraints.max.is_some() {
+        // The lowest value of each integer type is used by MSI to represent null so it is never a
+        // valid bound.
+        let (lowest, highest) = match category {
+            msi::Category::Integer => (-i32::from(i16::MAX), i32::from(i16::MAX)),
+            _ => (-i32::MAX, i32::MAX),
+        };
+        let min = constraints.min.unwrap_or(lowest);
+        let max = constraints.max.unwrap_or(highest);
+        tokens.extend(quote! { .range(#min, #max) });
+    }
+    if let Some(set) = &constraints.set {
+        let values = set.iter().map(ToString::to_string);
+        tokens.extend(quote! { .enum_values(&[#(#values),*]) });
+    }
+    tokens
+}
+
+fn generate_finish_build_for_field(
+    field: &FieldInformation,
+    category: msi::Category,
+) -> darling::Result<TokenStream> {
     match category {
-        msi::Category::Integer => quote! {.int16()},
-        msi::Category::DoubleInteger => quote! {.int32()},
+        msi::Category::Integer => Ok(quote! {.int16()}),
+        msi::Category::DoubleInteger => Ok(quote! {.int32()}),
         _ => {
-            let length = field.clone().length.unwrap_or_else(|| {
-                panic!(
-                    "Field {:?} with category {} must define a length",
-                    field.ident, category_str
-                )
-            });
-            quote! {.string(#length)}
+            let Some(length) = field.column_length() else {
+                return Err(darling::Error::custom(format!(
+                    "Field `{}` with category {category} must define a length",
+                    field.ident()
+                ))
+                .with_span(field.ident()));
+            };
+            Ok(quote! {.string(#length)})
         }
     }
 }
@@ -0,0 +1,109 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::{
+    constraint::ColumnConstraints,
+    helper::*,
+    msi_tables::{FieldInformation, TableList},
+    paths::CratePaths,
+};
+
+/// Generates `validation_rows` for a table. Each row follows the column order of the `_Validation`
+/// table: Table, Column, Nullable, MinValue, MaxValue, KeyTable, KeyColumn, Category, Set and
+/// Description.
+///
+/// Problems with the columns are reported by the column definitions so nothing is generated here
+/// when a column is invalid.
+pub fn generate_validation_tokens(
+    target_name: &str,
+    fields: &[FieldInformation],
+    tables: &TableList,
+    paths: &CratePaths,
+) -> TokenStream {
+    let Ok(rows) = fields
+        .iter()
+        .map(|field| generate_validation_row(target_name, field, tables, paths))
+        .collect::<darling::Result<Vec<_>>>()
+    else {
+        return TokenStream::new();
+    };
+
+    let msi = &paths.msi;
+    let table_ident = table_from_name(target_name);
+    let validation_comment = &format!(
+        "The rows of the `_Validation` table that describe the columns of the `{target_name}` \
+        table."
+    );
+    quote! {
+        impl #table_ident {
+            #[doc = #validation_comment]
+            pub fn validation_rows() -> ::std::vec::Vec<::std::vec::Vec<#msi::Value>> {
+                ::std::vec![
+                    #( #rows , )*
+                ]
+            }
+        }
+    }
+}
+
+fn generate_validation_row(
+    target_name: &str,
+    field: &FieldInformation,
+    tables: &TableList,
+    paths: &CratePaths,
+) -> darling::Result<TokenStream> {
+    let msi = &paths.msi;
+    let str_value = |value: &str| quote! { #msi::Value::Str(::std::string::String::from(#value)) };
+    let int_value = |value: i32| quote! { #msi::Value::Int(#value) };
+    let null = quote! { #msi::Value::Null };
+
+    let table = str_value(target_name);
+    let column = str_value(&field.column_name());
+    let nullable = str_value(if field.is_nullable() { "Y" } else { "N" });
+
+    let (key_table, key_column) = match field.foreign_key() {
+        Some(foreign_key) => {
+            // KeyColumn is 1-based.
+            let key_column = tables.foreign_key_index(foreign_key)? + 1;
+            (
+                str_value(&foreign_key.value()),
+                int_value(key_column as i32),
+            )
+        }
+        None => (null.clone(), null.clone()),
+    };
+
+    // Integer columns are described by their MinValue and MaxValue instead of a category.
+    let category = match field.msi_category()? {
+        msi::Category::Integer | msi::Category::DoubleInteger => null.clone(),
+        category => str_value(&category.to_string()),
+    };
+
+    let description = match field.description() {
+        Some(description) => str_value(&description),
+        None => null.clone(),
+    };
+
+    let constraints = ColumnConstraints::parse(field)?;
+    let min_value = constraints.min.map_or_else(|| null.clone(), int_value);
+    let max_value = constraints.max.map_or_else(|| null.clone(), int_value);
+    let set = match constraints.set_string() {
+        Some(set) => str_value(&set),
+        None => null,
+    };
+
+    Ok(quote! {
+        ::std::vec![
+            #table,
+            #column,
+            #nullable,
+            #min_value,
+            #max_value,
+            #key_table,
+            #key_column,
+            #category,
+            #set,
+            #description,
+        ]
+    })
+}
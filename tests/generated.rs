@@ -0,0 +1,341 @@
+//! Compiles tables with the macros and checks the generated code at runtime. The snapshot tests in
+//! `src/msi_tables/tests.rs` only compare tokens, these make sure the bookkeeping of the indexes
+//! and the order of `remove_cascade` hold up when the code runs.
+
+use whimsi_lib::tables::MsiTableKind;
+use whimsi_table_macro::{MsiTable, msi_table_list};
+
+/// The parts of whimsi-lib that the generated code refers to.
+#[allow(dead_code)]
+mod whimsi_lib {
+    pub mod types {
+        pub mod column {
+            pub mod identifier {
+                #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+                pub struct Identifier(pub String);
+
+                pub trait ToIdentifier {
+                    fn to_identifier(&self) -> Identifier;
+                }
+
+                impl ToIdentifier for String {
+                    fn to_identifier(&self) -> Identifier {
+                        Identifier(self.clone())
+                    }
+                }
+            }
+        }
+
+        pub mod helpers {
+            pub mod id_generator {
+                use std::{cell::RefCell, rc::Rc, str::FromStr};
+
+                use crate::whimsi_lib::types::column::identifier::{Identifier, ToIdentifier};
+
+                pub trait IdentifierGenerator {
+                    type IdentifierType: FromStr + ToIdentifier;
+                    fn id_prefix(&self) -> &str;
+                    fn used(&self) -> &Rc<RefCell<Vec<Identifier>>>;
+                    fn count(&self) -> usize;
+                    fn count_mut(&mut self) -> &mut usize;
+                }
+            }
+        }
+    }
+
+    pub mod tables {
+        use crate::whimsi_lib::types::column::identifier::Identifier;
+
+        pub trait MsiDao {
+            fn conflicts_with(&self, other: &Self) -> bool;
+            fn to_row(&self) -> Vec<msi::Value>;
+        }
+
+        pub trait PrimaryIdentifier {
+            fn primary_identifier(&self) -> Option<Identifier>;
+        }
+
+        pub trait MsiTableKind {
+            type TableValue;
+            fn name(&self) -> &'static str;
+            fn entries(&self) -> &Vec<Self::TableValue>;
+            fn entries_mut(&mut self) -> &mut Vec<Self::TableValue>;
+            fn primary_key_indices(&self) -> Vec<usize>;
+            fn columns(&self) -> Vec<msi::Column>;
+        }
+    }
+}
+
+msi_table_list! {
+    #[msi_table(crate = "crate::whimsi_lib")]
+    enum Tables {
+        Directory {
+            #[msi_column(primary_key, category = msi::Category::Identifier, length = 72)]
+            directory: String,
+            #[msi_column(
+                identifier(foreign_key = "Directory"),
+                category = msi::Category::Identifier,
+                length = 72,
+                column_name = "Directory_Parent"
+            )]
+            parent: Option<String>,
+            #[msi_column(indexed, category = msi::Category::Text, length = 255)]
+            default_dir: String,
+        },
+        Component {
+            #[msi_column(primary_key, category = msi::Category::Identifier, length = 72)]
+            component: String,
+            #[msi_column(
+                identifier(foreign_key = "Directory"),
+                category = msi::Category::Identifier,
+                length = 72
+            )]
+            directory_: String,
+        },
+        Shortcut {
+            #[msi_column(primary_key, category = msi::Category::Identifier, length = 72)]
+            shortcut: String,
+            #[msi_column(
+                nullable,
+                identifier(foreign_key = "Directory"),
+                category = msi::Category::Identifier,
+                length = 72
+            )]
+            directory_: MaybeText,
+        },
+        Note {
+            #[msi_column(
+                identifier(foreign_key = "Component"),
+                category = msi::Category::Identifier,
+                length = 72
+            )]
+            component_: String,
+            #[msi_column(category = msi::Category::Text, length = 255)]
+            text: String,
+        },
+    }
+}
+
+type MaybeText = Option<String>;
+
+#[derive(Clone, Debug, PartialEq, MsiTable)]
+#[msi_table(crate = "crate::whimsi_lib")]
+pub struct DocumentDao {
+    #[msi_column(primary_key, category = msi::Category::Integer)]
+    id: i16,
+    #[msi_column(nullable, unique, indexed, category = msi::Category::Text, length = 64)]
+    title: MaybeText,
+}
+
+fn directory(directory: &str, parent: Option<&str>, default_dir: &str) -> DirectoryDao {
+    DirectoryDao::new(
+        directory.to_string(),
+        parent.map(str::to_string),
+        default_dir.to_string(),
+    )
+}
+
+fn component(component: &str, directory: &str) -> ComponentDao {
+    ComponentDao::new(component.to_string(), directory.to_string())
+}
+
+fn note(component: &str, text: &str) -> NoteDao {
+    NoteDao::new(component.to_string(), text.to_string())
+}
+
+fn directories(table: &DirectoryTable, default_dir: &str) -> Vec<String> {
+    let default_dir = default_dir.to_string();
+    table
+        .find_by_default_dir(&default_dir)
+        .map(|entry| entry.directory.clone())
+        .collect()
+}
+
+fn key(value: &str) -> Vec<msi::Value> {
+    vec![msi::ToValue::to_value(&value.to_string())]
+}
+
+/// Directories `A` to `C` nested in each other, with a component in `B` and one in `C`. The
+/// component in `B` has a note, which is a table without a primary key.
+fn nested() -> TablesContainer {
+    let mut tables = TablesContainer::new();
+    tables.directory.insert(directory("A", None, "a"));
+    tables.directory.insert(directory("B", Some("A"), "b"));
+    tables.directory.insert(directory("C", Some("B"), "c"));
+    tables.component.insert(component("X", "B"));
+    tables.component.insert(component("Y", "C"));
+    tables.note.insert(note("X", "x"));
+    tables
+}
+
+#[test]
+fn removed_entries_leave_the_secondary_index() {
+    let mut table = DirectoryTable::default();
+    table.insert(directory("A", None, "shared"));
+    table.insert(directory("B", None, "other"));
+    table.insert(directory("C", None, "shared"));
+
+    table.remove(0).unwrap();
+
+    assert_eq!(directories(&table, "shared"), ["C"]);
+    assert_eq!(directories(&table, "other"), ["B"]);
+    // The positions after the removed entry moved down with the entries.
+    table.remove_by_key(&"C".to_string().into()).unwrap();
+    assert!(directories(&table, "shared").is_empty());
+    assert_eq!(directories(&table, "other"), ["B"]);
+}
+
+#[test]
+fn upsert_moves_the_replaced_entry_in_the_secondary_index() {
+    let mut table = DirectoryTable::default();
+    table.insert(directory("A", None, "old"));
+    table.insert(directory("B", None, "old"));
+
+    let replaced = table.upsert(directory("A", None, "new")).unwrap();
+
+    assert_eq!(replaced, Some(directory("A", None, "old")));
+    assert_eq!(directories(&table, "old"), ["B"]);
+    assert_eq!(directories(&table, "new"), ["A"]);
+    assert_eq!(table.entries.len(), 2);
+}
+
+#[test]
+fn indexes_are_rebuilt_after_entries_are_changed_directly() {
+    let mut table = DirectoryTable::default();
+    table.insert(directory("A", None, "a"));
+    table.insert(directory("B", None, "b"));
+
+    let entries = table.entries_mut();
+    entries.swap(0, 1);
+    entries[0].default_dir = "changed".to_string();
+
+    assert_eq!(table[String::from("A")].default_dir, "a");
+    assert_eq!(table[String::from("B")].default_dir, "changed");
+    assert_eq!(directories(&table, "changed"), ["B"]);
+    assert!(directories(&table, "b").is_empty());
+
+    // Adding a row rebuilds the indexes from the changed entries.
+    table.insert(directory("C", None, "changed"));
+    assert_eq!(directories(&table, "changed"), ["B", "C"]);
+    assert!(table.try_insert(directory("A", None, "a")).is_err());
+    assert_eq!(
+        table.remove_by_key(&"A".to_string().into()).unwrap(),
+        Some(directory("A", None, "a"))
+    );
+    assert_eq!(table[String::from("C")].default_dir, "changed");
+    assert_eq!(directories(&table, "changed"), ["B", "C"]);
+}
+
+#[test]
+fn nullable_alias_columns_are_unique_and_indexed_by_their_values() {
+    let mut table = DocumentTable::default();
+    table.insert(DocumentDao::new(1i16, None));
+
+    // Rows without a title do not conflict with each other.
+    assert!(table.try_insert(DocumentDao::new(2i16, None)).is_ok());
+    table.insert(DocumentDao::new(3i16, Some("Readme".to_string())));
+    assert!(
+        table
+            .try_insert(DocumentDao::new(4i16, Some("Readme".to_string())))
+            .is_err()
+    );
+
+    let title = "Readme".to_string();
+    let found = table
+        .find_by_title(&title)
+        .map(|entry| entry.id)
+        .collect::<Vec<_>>();
+    assert_eq!(found, [3]);
+}
+
+#[test]
+fn remove_cascade_deletes_every_referencing_row() {
+    let mut tables = nested();
+
+    let removed = tables
+        .remove_cascade(TablesKind::Directory, &key("B"), TablesCascade::Delete)
+        .unwrap();
+
+    // Each removed row is followed by the rows that reference it, the last found first.
+    assert_eq!(
+        removed,
+        [
+            TablesDao::Directory(directory("B", Some("A"), "b")),
+            TablesDao::Component(component("X", "B")),
+            TablesDao::Note(note("X", "x")),
+            TablesDao::Directory(directory("C", Some("B"), "c")),
+            TablesDao::Component(component("Y", "C")),
+        ]
+    );
+    assert_eq!(tables.directory.entries, [directory("A", None, "a")]);
+    assert!(tables.component.entries.is_empty());
+    assert!(tables.note.entries.is_empty());
+    assert!(tables.check_references().is_empty());
+}
+
+#[test]
+fn remove_cascade_nulls_out_optional_references() {
+    let mut tables = nested();
+
+    let removed = tables
+        .remove_cascade(TablesKind::Directory, &key("B"), TablesCascade::NullOut)
+        .unwrap();
+
+    assert_eq!(
+        removed,
+        [
+            TablesDao::Directory(directory("B", Some("A"), "b")),
+            TablesDao::Component(component("X", "B")),
+            TablesDao::Note(note("X", "x")),
+        ]
+    );
+    assert_eq!(tables.directory[String::from("C")].parent, None);
+    assert_eq!(tables.component.entries, [component("Y", "C")]);
+    assert!(tables.check_references().is_empty());
+    // The key index is rebuilt after the references were set to null.
+    assert!(tables.directory.contains_key(&"C".to_string().into()));
+}
+
+#[test]
+fn remove_cascade_ignores_missing_rows() {
+    let mut tables = nested();
+
+    let removed = tables
+        .remove_cascade(TablesKind::Directory, &key("Z"), TablesCascade::Delete)
+        .unwrap();
+
+    assert!(removed.is_empty());
+    assert_eq!(tables.directory.entries.len(), 3);
+    assert_eq!(tables.component.entries.len(), 2);
+}
+
+#[test]
+fn remove_cascade_finds_rows_without_a_primary_key_by_their_values() {
+    let mut tables = nested();
+    tables.note.insert(note("Y", "y"));
+
+    let values = [msi::Value::from("Y"), msi::Value::from("y")];
+    let removed = tables
+        .remove_cascade(TablesKind::Note, &values, TablesCascade::Delete)
+        .unwrap();
+
+    assert_eq!(removed, [TablesDao::Note(note("Y", "y"))]);
+    assert_eq!(tables.note.entries, [note("X", "x")]);
+    assert_eq!(tables.component.entries.len(), 2);
+}
+
+#[test]
+fn remove_cascade_nulls_out_nullable_alias_references() {
+    let mut tables = nested();
+    let shortcut = ShortcutDao::new("S".to_string(), Some("B".to_string()));
+    tables.shortcut.insert(shortcut);
+
+    tables
+        .remove_cascade(TablesKind::Directory, &key("B"), TablesCascade::NullOut)
+        .unwrap();
+
+    assert_eq!(
+        tables.shortcut.entries,
+        [ShortcutDao::new("S".to_string(), None)]
+    );
+}